@@ -0,0 +1,549 @@
+//! Derive macros for [`aoclib`](https://aoclib-docs.netlify.app/aoclib/).
+//!
+//! These are re-exported by `aoclib` when its `derive` feature is enabled. Depend on that
+//! feature rather than on this crate directly; the generated code refers to `aoclib`.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, spanned::Spanned, Attribute, Data, DeriveInput, Error, Fields, LitStr,
+    Result,
+};
+
+/// Derive `FromStr` for a struct or enum from a description of its textual form.
+///
+/// The generated `FromStr` implementation produces `aoclib::input::AocParseError`, which
+/// records the column at which parsing failed.
+///
+/// ## Structs
+///
+/// A struct must carry exactly one of these attributes:
+///
+/// - `#[aoc(template = "move {count} from {from} to {to}")]`: the input must match the template.
+///   Each placeholder is parsed into a field with that field's `FromStr` implementation.
+///   Placeholders may name a field (`{count}`), give the index of a tuple struct field (`{0}`),
+///   or be empty (`{}`), which selects the first field not otherwise named. Every field must
+///   appear in the template exactly once. Literal braces are written `{{` and `}}`.
+/// - `#[aoc(ints)]`: every signed integer is extracted from the input, and the integers are
+///   parsed into the fields in declaration order. There must be exactly as many integers as fields.
+///
+/// ## Enums
+///
+/// Each variant is tried in declaration order, and the first which parses successfully is
+/// returned. If none do, the error from the variant which progressed furthest is reported.
+///
+/// A variant may carry `#[aoc(template = "...")]` or `#[aoc(ints)]`, which behave as they do for
+/// structs. A unit variant may instead carry `#[aoc(keyword = "...")]`, in which case the input
+/// must equal the keyword exactly. A unit variant with no attribute uses its name in
+/// `snake_case` as its keyword.
+///
+/// ## Fields
+///
+/// Within a template, a field may carry:
+///
+/// - `#[aoc(sep = ",")]`: the captured text is split on the separator, each item is trimmed, and
+///   the items are collected into the field, which must implement `FromIterator`. Repeating the
+///   attribute nests lists: `#[aoc(sep = ";", sep = ",")]` parses `1,2; 3` into `vec![vec![1, 2], vec![3]]`.
+///   Empty text produces an empty list.
+/// - `#[aoc(ints)]`: every signed integer is extracted from the captured text and collected
+///   into the field, which must implement `FromIterator`.
+///
+/// Fields whose types implement `FromStr` themselves, such as `aoclib::CommaSep`, need no attribute.
+///
+/// ## Example
+///
+/// ```
+/// use aoclib::AocParse;
+///
+/// #[derive(Debug, PartialEq, AocParse)]
+/// #[aoc(template = "move {count} from {from} to {to}")]
+/// struct Move {
+///     count: u32,
+///     from: usize,
+///     to: usize,
+/// }
+///
+/// #[derive(Debug, PartialEq, AocParse)]
+/// enum Instruction {
+///     Noop,
+///     #[aoc(template = "addx {}")]
+///     Addx(i32),
+/// }
+///
+/// assert_eq!(
+///     "move 3 from 1 to 2".parse::<Move>().unwrap(),
+///     Move { count: 3, from: 1, to: 2 },
+/// );
+/// assert_eq!("addx -5".parse::<Instruction>().unwrap(), Instruction::Addx(-5));
+/// assert_eq!(
+///     "move x from 1 to 2".parse::<Move>().unwrap_err().to_string(),
+///     r#"column 6: field `count` (u32) from "x": invalid digit found in string"#,
+/// );
+/// ```
+#[proc_macro_derive(AocParse, attributes(aoc))]
+pub fn derive_aoc_parse(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Attributes which describe how an entire struct or variant is parsed.
+#[derive(Default)]
+struct BodyAttrs {
+    template: Option<LitStr>,
+    keyword: Option<LitStr>,
+    ints: bool,
+}
+
+impl BodyAttrs {
+    fn parse(attrs: &[Attribute]) -> Result<BodyAttrs> {
+        let mut out = BodyAttrs::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("aoc")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("template") {
+                    out.template = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("keyword") {
+                    out.keyword = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("ints") {
+                    out.ints = true;
+                } else {
+                    return Err(meta.error("expected `template`, `keyword`, or `ints`"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(out)
+    }
+}
+
+/// Attributes which describe how a single field is parsed.
+#[derive(Default)]
+struct FieldAttrs {
+    separators: Vec<LitStr>,
+    ints: bool,
+}
+
+impl FieldAttrs {
+    fn parse(attrs: &[Attribute]) -> Result<FieldAttrs> {
+        let mut out = FieldAttrs::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("aoc")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("sep") {
+                    out.separators.push(meta.value()?.parse()?);
+                } else if meta.path.is_ident("ints") {
+                    out.ints = true;
+                } else {
+                    return Err(meta.error("expected `sep` or `ints`"));
+                }
+                Ok(())
+            })?;
+            if out.ints && !out.separators.is_empty() {
+                return Err(Error::new(
+                    attr.span(),
+                    "`ints` and `sep` cannot be combined on one field",
+                ));
+            }
+        }
+        Ok(out)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.separators.is_empty() && !self.ints
+    }
+}
+
+/// How a struct or variant is parsed.
+enum Body {
+    /// Match a template; `placeholders[i]` is the index of the field parsed from placeholder `i`.
+    Template {
+        template: String,
+        placeholders: Vec<usize>,
+    },
+    /// Extract integers into each field in order.
+    Ints,
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let private = private();
+
+    let from_str_body = match &input.data {
+        Data::Struct(data) => {
+            let attrs = BodyAttrs::parse(&input.attrs)?;
+            if let Some(keyword) = &attrs.keyword {
+                return Err(Error::new(
+                    keyword.span(),
+                    "`keyword` applies only to enum variants",
+                ));
+            }
+            let body = Body::new(&attrs, &data.fields, input.span())?;
+            let label = syn::Lifetime::new("'aoc_parse", proc_macro2::Span::call_site());
+            let attempt = body.expand(&data.fields, quote!(Self), &label)?;
+            quote! {
+                #label: {
+                    #attempt
+                }
+            }
+        }
+        Data::Enum(data) => {
+            let mut attempts = Vec::with_capacity(data.variants.len());
+            for variant in &data.variants {
+                let mut attrs = BodyAttrs::parse(&variant.attrs)?;
+                let variant_ident = &variant.ident;
+                if attrs.template.is_none() && !attrs.ints {
+                    if !matches!(variant.fields, Fields::Unit) {
+                        return Err(Error::new(
+                            variant.span(),
+                            "a variant with fields requires `#[aoc(template = \"...\")]` or `#[aoc(ints)]`",
+                        ));
+                    }
+                    let keyword = attrs.keyword.take().unwrap_or_else(|| {
+                        LitStr::new(
+                            &snake_case(&variant_ident.to_string()),
+                            variant_ident.span(),
+                        )
+                    });
+                    attrs.template = Some(LitStr::new(
+                        &keyword.value().replace('{', "{{").replace('}', "}}"),
+                        keyword.span(),
+                    ));
+                } else if let Some(keyword) = &attrs.keyword {
+                    return Err(Error::new(
+                        keyword.span(),
+                        "`keyword` cannot be combined with `template` or `ints`",
+                    ));
+                }
+                let body = Body::new(&attrs, &variant.fields, variant.span())?;
+                let label = syn::Lifetime::new(
+                    &format!("'aoc_parse_{}", snake_case(&variant_ident.to_string())),
+                    proc_macro2::Span::call_site(),
+                );
+                let attempt = body.expand(&variant.fields, quote!(Self::#variant_ident), &label)?;
+                let variant_name = variant_ident.to_string();
+                attempts.push(quote! {
+                    let __attempt: ::std::result::Result<Self, #private::AocParseError> = #label: {
+                        #attempt
+                    };
+                    match __attempt {
+                        ::std::result::Result::Ok(value) => return ::std::result::Result::Ok(value),
+                        ::std::result::Result::Err(err) => __errors.push((#variant_name, err)),
+                    }
+                });
+            }
+            quote! {
+                let mut __errors = ::std::vec::Vec::new();
+                #( #attempts )*
+                ::std::result::Result::Err(#private::no_variant_matched(__errors))
+            }
+        }
+        Data::Union(data) => {
+            return Err(Error::new(
+                data.union_token.span(),
+                "AocParse cannot be derived for unions",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::std::str::FromStr for #name #ty_generics #where_clause {
+            type Err = #private::AocParseError;
+
+            fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+                #from_str_body
+            }
+        }
+    })
+}
+
+/// Path to the runtime support module within `aoclib`.
+fn private() -> TokenStream {
+    quote!(::aoclib::input::__aoc_parse)
+}
+
+impl Body {
+    fn new(attrs: &BodyAttrs, fields: &Fields, span: proc_macro2::Span) -> Result<Body> {
+        match (&attrs.template, attrs.ints) {
+            (Some(template), false) => {
+                let (template, placeholders) = compile_template(template, fields)?;
+                Ok(Body::Template {
+                    template,
+                    placeholders,
+                })
+            }
+            (None, true) => {
+                for field in fields {
+                    if !FieldAttrs::parse(&field.attrs)?.is_empty() {
+                        return Err(Error::new(
+                            field.span(),
+                            "field attributes cannot be used with `#[aoc(ints)]`",
+                        ));
+                    }
+                }
+                Ok(Body::Ints)
+            }
+            (Some(template), true) => Err(Error::new(
+                template.span(),
+                "`template` and `ints` cannot be combined",
+            )),
+            (None, false) => Err(Error::new(
+                span,
+                "expected `#[aoc(template = \"...\")]` or `#[aoc(ints)]`",
+            )),
+        }
+    }
+
+    /// Generate a block which evaluates to `Result<Self, AocParseError>`.
+    ///
+    /// Failures break out of the block labeled `label`, which must enclose the generated code.
+    fn expand(
+        &self,
+        fields: &Fields,
+        constructor: TokenStream,
+        label: &syn::Lifetime,
+    ) -> Result<TokenStream> {
+        let private = private();
+        let attempt = |expr: TokenStream| {
+            quote! {
+                match #expr {
+                    ::std::result::Result::Ok(value) => value,
+                    ::std::result::Result::Err(err) => break #label ::std::result::Result::Err(err),
+                }
+            }
+        };
+
+        let field_vars: Vec<_> = (0..fields.len())
+            .map(|idx| format_ident!("__field_{}", idx))
+            .collect();
+
+        let mut statements = Vec::new();
+        match self {
+            Body::Template {
+                template,
+                placeholders,
+            } => {
+                let captures = attempt(quote!(#private::captures(__pattern, s)));
+                statements.push(quote! {
+                    static __PATTERN: #private::OnceLock<#private::ScanPattern> = #private::OnceLock::new();
+                    let __pattern = __PATTERN.get_or_init(|| {
+                        #private::ScanPattern::new(#template)
+                            .expect("template was validated at compile time")
+                    });
+                    let __captures = #captures;
+                });
+                for (capture_idx, &field_idx) in placeholders.iter().enumerate() {
+                    let field = fields
+                        .iter()
+                        .nth(field_idx)
+                        .expect("placeholder names a field");
+                    let var = &field_vars[field_idx];
+                    let ty = &field.ty;
+                    let name = field_name(field, field_idx);
+                    let text = quote!(__captures[#capture_idx]);
+                    let value = attempt(parse_field_expr(
+                        &FieldAttrs::parse(&field.attrs)?,
+                        ty,
+                        &name,
+                        text,
+                    ));
+                    statements.push(quote!(let #var: #ty = #value;));
+                }
+            }
+            Body::Ints => {
+                statements.push(quote!(let mut __ints = #private::int_strs(s);));
+                for (idx, field) in fields.iter().enumerate() {
+                    let var = &field_vars[idx];
+                    let ty = &field.ty;
+                    let name = field_name(field, idx);
+                    let text = attempt(quote!(#private::next_int(s, &mut __ints, #name)));
+                    let value = attempt(quote!(#private::parse_field::<#ty>(s, #text, #name)));
+                    statements.push(quote!(let #var: #ty = #value;));
+                }
+                let done = attempt(quote!(#private::no_more_ints(s, &mut __ints)));
+                statements.push(quote!(#done;));
+            }
+        }
+
+        let construct = match fields {
+            Fields::Named(named) => {
+                let idents = named.named.iter().map(|field| &field.ident);
+                quote!(#constructor { #( #idents: #field_vars ),* })
+            }
+            Fields::Unnamed(_) => quote!(#constructor( #( #field_vars ),* )),
+            Fields::Unit => constructor,
+        };
+
+        Ok(quote! {
+            #( #statements )*
+            ::std::result::Result::Ok(#construct)
+        })
+    }
+}
+
+/// The name of a field as it appears in error messages.
+fn field_name(field: &syn::Field, idx: usize) -> String {
+    match &field.ident {
+        Some(ident) => ident.to_string(),
+        None => idx.to_string(),
+    }
+}
+
+/// Generate an expression of type `Result<#ty, AocParseError>` parsing `text` into a field.
+fn parse_field_expr(
+    attrs: &FieldAttrs,
+    ty: &syn::Type,
+    name: &str,
+    text: TokenStream,
+) -> TokenStream {
+    let private = private();
+    if attrs.ints {
+        return quote! {
+            #private::int_strs(#text)
+                .map(|__item| #private::parse_field(s, __item, #name))
+                .collect::<::std::result::Result<#ty, #private::AocParseError>>()
+        };
+    }
+    if attrs.separators.is_empty() {
+        return quote!(#private::parse_field::<#ty>(s, #text, #name));
+    }
+
+    let mut expr = quote!(#private::parse_field(s, __text, #name));
+    for separator in attrs.separators.iter().rev() {
+        expr = quote! {
+            #private::split(__text, #separator)
+                .map(|__text| #expr)
+                .collect::<::std::result::Result<_, #private::AocParseError>>()
+        };
+    }
+    quote! {
+        {
+            let __text: &str = #text;
+            let __value: ::std::result::Result<#ty, #private::AocParseError> = #expr;
+            __value
+        }
+    }
+}
+
+/// Validate a template, converting it into the form accepted by `ScanPattern` and resolving
+/// each placeholder to the index of the field it names.
+fn compile_template(lit: &LitStr, fields: &Fields) -> Result<(String, Vec<usize>)> {
+    let source = lit.value();
+    let error = |message: String| Error::new(lit.span(), message);
+
+    let mut template = String::with_capacity(source.len());
+    let mut names = Vec::new();
+    let mut literal_since_placeholder = true;
+    let mut chars = source.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                template.push_str("{{");
+                literal_since_placeholder = true;
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                template.push_str("}}");
+                literal_since_placeholder = true;
+            }
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some('{') | None => {
+                            return Err(error("unterminated placeholder in template".into()))
+                        }
+                        Some(ch) => name.push(ch),
+                    }
+                }
+                if !literal_since_placeholder && !names.is_empty() {
+                    return Err(error(format!(
+                        "placeholder {{{}}} must be separated from the previous placeholder by literal text",
+                        name
+                    )));
+                }
+                template.push_str("{}");
+                names.push(name.trim().to_owned());
+                literal_since_placeholder = false;
+            }
+            '}' => {
+                return Err(error(
+                    "unmatched `}` in template; use `}}` for a literal brace".into(),
+                ))
+            }
+            ch => {
+                template.push(ch);
+                literal_since_placeholder = true;
+            }
+        }
+    }
+
+    let mut assigned = vec![false; fields.len()];
+    let mut placeholders = Vec::with_capacity(names.len());
+    // resolve explicit names first, so that `{}` can select the fields which remain
+    let mut resolved: Vec<Option<usize>> = Vec::with_capacity(names.len());
+    for name in &names {
+        if name.is_empty() {
+            resolved.push(None);
+            continue;
+        }
+        let idx = fields
+            .iter()
+            .enumerate()
+            .find(|(idx, field)| match &field.ident {
+                Some(ident) => ident == name,
+                None => idx.to_string() == *name,
+            })
+            .map(|(idx, _)| idx)
+            .ok_or_else(|| error(format!("template names unknown field `{}`", name)))?;
+        if std::mem::replace(&mut assigned[idx], true) {
+            return Err(error(format!(
+                "field `{}` appears more than once in template",
+                name
+            )));
+        }
+        resolved.push(Some(idx));
+    }
+    for slot in resolved {
+        let idx = match slot {
+            Some(idx) => idx,
+            None => {
+                let idx = assigned
+                    .iter()
+                    .position(|assigned| !assigned)
+                    .ok_or_else(|| error("template has more placeholders than fields".into()))?;
+                assigned[idx] = true;
+                idx
+            }
+        };
+        placeholders.push(idx);
+    }
+
+    if let Some(idx) = assigned.iter().position(|assigned| !assigned) {
+        let field = fields.iter().nth(idx).expect("idx < fields.len()");
+        return Err(error(format!(
+            "field `{}` does not appear in template",
+            field_name(field, idx)
+        )));
+    }
+
+    Ok((template, placeholders))
+}
+
+/// Convert a `CamelCase` identifier into `snake_case`.
+fn snake_case(ident: &str) -> String {
+    let mut out = String::with_capacity(ident.len() + 4);
+    for (idx, ch) in ident.chars().enumerate() {
+        if ch.is_uppercase() {
+            if idx != 0 {
+                out.push('_');
+            }
+            out.extend(ch.to_lowercase());
+        } else {
+            out.push(ch);
+        }
+    }
+    out
+}
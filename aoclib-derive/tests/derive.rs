@@ -0,0 +1,172 @@
+//! Run with: `cargo test -p aoclib-derive --test derive`
+
+use aoclib::{input::AocParseError, AocParse};
+
+#[derive(Debug, PartialEq, AocParse)]
+#[aoc(template = "move {count} from {from} to {to}")]
+struct Move {
+    count: u32,
+    from: usize,
+    to: usize,
+}
+
+#[derive(Debug, PartialEq, AocParse)]
+#[aoc(template = "{1}-{0} {{{}}}")]
+struct Tuple(u8, u8, char);
+
+#[derive(Debug, PartialEq, AocParse)]
+#[aoc(ints)]
+struct Sensor {
+    x: i32,
+    y: i32,
+    beacon_x: i32,
+    beacon_y: i32,
+}
+
+#[derive(Debug, PartialEq, AocParse)]
+#[aoc(template = "{name}: {values}")]
+struct Nested {
+    name: String,
+    #[aoc(sep = ";", sep = ",")]
+    values: Vec<Vec<u32>>,
+}
+
+#[derive(Debug, PartialEq, AocParse)]
+#[aoc(template = "Card {id}: {numbers}")]
+struct Card {
+    id: u32,
+    #[aoc(ints)]
+    numbers: Vec<i64>,
+}
+
+#[derive(Debug, PartialEq, AocParse)]
+enum Instruction {
+    Noop,
+    #[aoc(keyword = "halt!")]
+    Halt,
+    #[aoc(template = "addx {}")]
+    Addx(i32),
+    #[aoc(template = "jump {offset} if {register}")]
+    Jump {
+        offset: i32,
+        register: char,
+    },
+}
+
+#[derive(Debug, PartialEq, AocParse)]
+#[aoc(template = "<{}>")]
+struct Wrapper<T>(T)
+where
+    T: std::str::FromStr,
+    <T as std::str::FromStr>::Err: std::fmt::Display;
+
+#[test]
+fn test_named_template() {
+    assert_eq!(
+        "move 3 from 1 to 2".parse(),
+        Ok(Move {
+            count: 3,
+            from: 1,
+            to: 2
+        })
+    );
+}
+
+#[test]
+fn test_positional_template_and_escapes() {
+    assert_eq!("2-1 {x}".parse(), Ok(Tuple(1, 2, 'x')));
+}
+
+#[test]
+fn test_ints() {
+    assert_eq!(
+        "Sensor at x=2, y=-18: closest beacon is at x=-2, y=15".parse(),
+        Ok(Sensor {
+            x: 2,
+            y: -18,
+            beacon_x: -2,
+            beacon_y: 15
+        })
+    );
+    assert_eq!(
+        "x=1 y=2 x=3".parse::<Sensor>(),
+        Err(AocParseError::new(
+            12,
+            "expected an integer for field `beacon_y`"
+        ))
+    );
+    assert_eq!(
+        "1 2 3 4 5".parse::<Sensor>(),
+        Err(AocParseError::new(9, "unexpected extra integer \"5\""))
+    );
+}
+
+#[test]
+fn test_nested_separators() {
+    assert_eq!(
+        "a: 1,2; 3".parse(),
+        Ok(Nested {
+            name: "a".into(),
+            values: vec![vec![1, 2], vec![3]],
+        })
+    );
+    assert_eq!(
+        "b: ".parse(),
+        Ok(Nested {
+            name: "b".into(),
+            values: Vec::new(),
+        })
+    );
+}
+
+#[test]
+fn test_field_ints() {
+    assert_eq!(
+        "Card 1: 41 48 -83 | 86 6".parse(),
+        Ok(Card {
+            id: 1,
+            numbers: vec![41, 48, -83, 86, 6],
+        })
+    );
+}
+
+#[test]
+fn test_enum() {
+    assert_eq!("noop".parse(), Ok(Instruction::Noop));
+    assert_eq!("halt!".parse(), Ok(Instruction::Halt));
+    assert_eq!("addx -7".parse(), Ok(Instruction::Addx(-7)));
+    assert_eq!(
+        "jump -2 if a".parse(),
+        Ok(Instruction::Jump {
+            offset: -2,
+            register: 'a'
+        })
+    );
+}
+
+#[test]
+fn test_enum_reports_closest_variant() {
+    let err = "jump 2 if ab".parse::<Instruction>().unwrap_err();
+    assert_eq!(err.column, 11);
+    assert!(err
+        .message
+        .starts_with("no variant matched; closest was `Jump`"));
+}
+
+#[test]
+fn test_generic() {
+    assert_eq!("<12>".parse(), Ok(Wrapper(12_u8)));
+}
+
+#[test]
+fn test_error_column() {
+    let err = "move 3 from x to 2".parse::<Move>().unwrap_err();
+    assert_eq!(err.column, 13);
+    assert_eq!(
+        err.to_string(),
+        r#"column 13: field `from` (usize) from "x": invalid digit found in string"#
+    );
+    // the literal " from " is sought from the start of the `count` placeholder
+    let err = "move 3 to 2".parse::<Move>().unwrap_err();
+    assert_eq!(err.column, 6);
+}
@@ -0,0 +1,58 @@
+//! Run with: `cargo bench --bench navigate`
+
+use aoclib::geometry::{map::Traversable, Map, Point};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+/// A serpentine maze: walls on every other column, with the gap alternating between the top and
+/// the bottom, so the only path visits nearly every free tile.
+fn serpentine(size: usize) -> Map<Traversable> {
+    let top = size as i32 - 1;
+    Map::procedural(size, size, |point| {
+        let gap = if point.x % 4 == 1 { top } else { 0 };
+        if point.x % 2 == 1 && point.y != gap {
+            Traversable::Obstructed
+        } else {
+            Traversable::Free
+        }
+    })
+}
+
+/// An open field with a deterministic scattering of obstacles.
+fn scattered(size: usize) -> Map<Traversable> {
+    Map::procedural(size, size, |point| {
+        let hash = (point.x as u32)
+            .wrapping_mul(0x9e37_79b9)
+            .wrapping_add((point.y as u32).wrapping_mul(0x85eb_ca6b))
+            .rotate_left(13)
+            .wrapping_mul(0xc2b2_ae35);
+        if hash % 10 < 3
+            && point != Point::new(0, 0)
+            && point != Point::new(size as i32 - 1, size as i32 - 1)
+        {
+            Traversable::Obstructed
+        } else {
+            Traversable::Free
+        }
+    })
+}
+
+fn navigate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("navigate");
+    group.sample_size(10);
+    for size in [101, 301] {
+        for (name, map) in [
+            ("serpentine", serpentine(size)),
+            ("scattered", scattered(size)),
+        ] {
+            let from = map.bottom_left();
+            let to = map.top_right();
+            group.bench_with_input(BenchmarkId::new(name, size), &map, |b, map| {
+                b.iter(|| map.navigate(from, to))
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, navigate);
+criterion_main!(benches);
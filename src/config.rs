@@ -166,8 +166,10 @@ day_template = "/future/days"
 
     #[test]
     fn can_serialize() {
-        let mut config = Config::default();
-        config.session = "foo bar session session".into();
+        let mut config = Config {
+            session: "foo bar session session".into(),
+            ..Config::default()
+        };
         config.paths.entry(1984).or_default().implementation =
             Some("/aoc/was/definitely/a/thing".into());
 
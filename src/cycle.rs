@@ -0,0 +1,249 @@
+//! Detect cycles in repeated simulations, and skip ahead through them.
+//!
+//! Many puzzles ask for the state of some simulation after an enormous number of steps. If the
+//! simulation is deterministic and has finitely many states, it must eventually repeat itself.
+//! Once it does, every later state is a repeat of a known one.
+//!
+//! Every function here takes an initial state and a step function producing the next state from
+//! the current one. They all loop forever if the simulation never repeats.
+//!
+//! ## Example
+//!
+//! ```
+//! # use aoclib::cycle;
+//! let step = |&x: &u32| (x * x + 1) % 255;
+//! let cycle = cycle::brent(&3, step);
+//! assert_eq!(cycle.offset, 2);
+//! assert_eq!(cycle.period, 6);
+//!
+//! let mut naive = 3;
+//! for _ in 0..1000 {
+//!     naive = step(&naive);
+//! }
+//! assert_eq!(cycle::state_after(3, step, 1000), naive);
+//! ```
+
+use std::{collections::HashMap, hash::Hash};
+
+/// Shape of the sequence of states produced by a simulation.
+///
+/// The states at steps `offset .. offset + period` are all distinct, and then repeat forever.
+/// No state before `offset` ever recurs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cycle {
+    /// Number of steps before the first state which is part of the cycle.
+    pub offset: usize,
+    /// Number of steps between repeats of any state within the cycle.
+    pub period: usize,
+}
+
+impl Cycle {
+    /// The smallest step count which produces the same state as `steps`.
+    pub fn equivalent_step(&self, steps: usize) -> usize {
+        if steps < self.offset {
+            steps
+        } else {
+            self.offset + (steps - self.offset) % self.period
+        }
+    }
+}
+
+/// Detect a cycle using Brent's algorithm.
+///
+/// This requires only constant memory and an `Eq` implementation, at the cost of computing each
+/// state more than once.
+// https://en.wikipedia.org/wiki/Cycle_detection#Brent's_algorithm
+pub fn brent<State>(initial: &State, mut step: impl FnMut(&State) -> State) -> Cycle
+where
+    State: Clone + Eq,
+{
+    // find the period: the hare searches successive powers of two ahead of the tortoise
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(initial);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare);
+        period += 1;
+    }
+
+    // find the offset: advance two states a period apart until they meet
+    let mut tortoise = initial.clone();
+    let mut hare = initial.clone();
+    for _ in 0..period {
+        hare = step(&hare);
+    }
+    let mut offset = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        offset += 1;
+    }
+
+    Cycle { offset, period }
+}
+
+/// Every state of a simulation up to the point where it begins to repeat.
+///
+/// Created by [`history`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct History<State> {
+    states: Vec<State>,
+    cycle: Cycle,
+}
+
+impl<State> History<State> {
+    /// The cycle which the simulation falls into.
+    pub fn cycle(&self) -> Cycle {
+        self.cycle
+    }
+
+    /// The state after the given number of steps.
+    pub fn state_after(&self, steps: usize) -> &State {
+        &self.states[self.cycle.equivalent_step(steps)]
+    }
+
+    /// Every distinct state, in order; the initial state is first.
+    pub fn states(&self) -> &[State] {
+        &self.states
+    }
+
+    /// Take ownership of every distinct state, in order; the initial state is first.
+    pub fn into_states(self) -> Vec<State> {
+        self.states
+    }
+}
+
+/// Detect a cycle by recording every state until one repeats.
+///
+/// This computes each state only once, and makes each available afterwards, at the cost of
+/// keeping them all in memory.
+pub fn history<State>(initial: State, mut step: impl FnMut(&State) -> State) -> History<State>
+where
+    State: Clone + Eq + Hash,
+{
+    let mut seen = HashMap::new();
+    let mut states = Vec::new();
+    let mut state = initial;
+    loop {
+        if let Some(&offset) = seen.get(&state) {
+            return History {
+                cycle: Cycle {
+                    offset,
+                    period: states.len() - offset,
+                },
+                states,
+            };
+        }
+        let next = step(&state);
+        seen.insert(state.clone(), states.len());
+        states.push(state);
+        state = next;
+    }
+}
+
+/// The state after the given number of steps, detecting a cycle by recording every state.
+///
+/// If the simulation does not repeat within `steps` steps, this simply returns the final state.
+/// See [`history`].
+pub fn state_after<State>(
+    initial: State,
+    mut step: impl FnMut(&State) -> State,
+    steps: usize,
+) -> State
+where
+    State: Clone + Eq + Hash,
+{
+    let mut seen = HashMap::new();
+    let mut states = Vec::new();
+    let mut state = initial;
+    while states.len() < steps {
+        if let Some(&offset) = seen.get(&state) {
+            let cycle = Cycle {
+                offset,
+                period: states.len() - offset,
+            };
+            return states.swap_remove(cycle.equivalent_step(steps));
+        }
+        let next = step(&state);
+        seen.insert(state.clone(), states.len());
+        states.push(state);
+        state = next;
+    }
+    state
+}
+
+/// The state after the given number of steps, detecting a cycle with Brent's algorithm.
+///
+/// This requires only constant memory and an `Eq` implementation. See [`brent`].
+pub fn state_after_brent<State>(
+    initial: State,
+    mut step: impl FnMut(&State) -> State,
+    steps: usize,
+) -> State
+where
+    State: Clone + Eq,
+{
+    let cycle = brent(&initial, &mut step);
+    let mut state = initial;
+    for _ in 0..cycle.equivalent_step(steps) {
+        state = step(&state);
+    }
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::{map::tile::Bool, Map, Point};
+
+    #[test]
+    fn test_algorithms_agree() {
+        for modulus in [7_u64, 100, 255, 1009, 65_537] {
+            let step = |&x: &u64| (x * x + 1) % modulus;
+            let history = history(0, step);
+            assert_eq!(brent(&0, step), history.cycle());
+            for steps in [0, 1, 5, 100, 1_000_000_000_000] {
+                let expect = *history.state_after(steps);
+                assert_eq!(state_after(0, step, steps), expect);
+                assert_eq!(state_after_brent(0, step, steps), expect);
+            }
+        }
+    }
+
+    #[test]
+    fn test_pure_cycle() {
+        let step = |&x: &u8| (x + 1) % 10;
+        assert_eq!(
+            brent(&4, step),
+            Cycle {
+                offset: 0,
+                period: 10
+            }
+        );
+        assert_eq!(history(4, step).states().len(), 10);
+        assert_eq!(state_after(4, step, 1_000_000_007), 1);
+    }
+
+    #[test]
+    fn test_map_rotation() {
+        let map = Map::procedural(3, 3, |point| Bool::from(point == Point::new(0, 1)));
+        let rotate = |map: &Map<Bool>| map.rotate_right();
+        assert_eq!(
+            brent(&map, rotate),
+            Cycle {
+                offset: 0,
+                period: 4
+            }
+        );
+        assert_eq!(
+            state_after(map.clone(), rotate, 1_000_000_001),
+            map.rotate_right()
+        );
+    }
+}
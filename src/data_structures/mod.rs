@@ -1 +1,5 @@
 //! Some data structures not provided by the standard library.
+
+mod nested_list;
+
+pub use nested_list::{Leaves, NestedList, NestedListError, Values};
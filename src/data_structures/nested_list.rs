@@ -0,0 +1,536 @@
+use std::{cmp::Ordering, fmt, iter::Enumerate, slice, str::FromStr};
+
+/// A recursive bracketed list, such as `[[1],[2,3,[4]]]`.
+///
+/// Each element is either a single value or a further list. Elements are separated by `,`;
+/// whitespace around values and brackets is ignored when parsing. A bare value, without
+/// brackets, is also accepted.
+///
+/// ## Ordering
+///
+/// Comparisons follow the rules common to Advent of Code puzzles:
+///
+/// - two values compare as `T` does
+/// - two lists compare lexicographically, element by element; if one is a prefix of the
+///   other, the shorter list is lesser
+/// - a value compared with a list is first wrapped in a single-element list
+///
+/// Equality is consistent with this ordering, so `1` and `[[1]]` are equal. For that reason
+/// `NestedList` does not implement `Hash`.
+///
+/// ## Paths
+///
+/// An element is addressed by a path: the sequence of indices taken from the root to reach it.
+/// The empty path addresses the root itself.
+///
+/// ## Example
+///
+/// ```
+/// # use aoclib::data_structures::NestedList;
+/// let left: NestedList<u32> = "[[1],[2,3,4]]".parse().unwrap();
+/// let right: NestedList<u32> = "[[1],4]".parse().unwrap();
+/// assert!(left < right);
+///
+/// let mut packet: NestedList<u32> = "[1,[2,[3,4]]]".parse().unwrap();
+/// assert_eq!(packet.values().copied().collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+///
+/// *packet.get_mut(&[1, 1]).unwrap() = NestedList::Value(0);
+/// assert_eq!(packet.to_string(), "[1,[2,0]]");
+/// ```
+#[derive(Debug, Clone)]
+pub enum NestedList<T> {
+    Value(T),
+    List(Vec<NestedList<T>>),
+}
+
+impl<T> Default for NestedList<T> {
+    /// An empty list.
+    fn default() -> Self {
+        NestedList::List(Vec::new())
+    }
+}
+
+impl<T> NestedList<T> {
+    /// `true` if this is a single value.
+    pub fn is_value(&self) -> bool {
+        matches!(self, NestedList::Value(_))
+    }
+
+    /// `true` if this is a list.
+    pub fn is_list(&self) -> bool {
+        matches!(self, NestedList::List(_))
+    }
+
+    /// Get the value, if this is a single value.
+    pub fn as_value(&self) -> Option<&T> {
+        match self {
+            NestedList::Value(value) => Some(value),
+            NestedList::List(_) => None,
+        }
+    }
+
+    /// Get the elements, if this is a list.
+    pub fn as_list(&self) -> Option<&[NestedList<T>]> {
+        match self {
+            NestedList::Value(_) => None,
+            NestedList::List(items) => Some(items),
+        }
+    }
+
+    /// Get the elements mutably, if this is a list.
+    pub fn as_list_mut(&mut self) -> Option<&mut Vec<NestedList<T>>> {
+        match self {
+            NestedList::Value(_) => None,
+            NestedList::List(items) => Some(items),
+        }
+    }
+
+    /// Maximum nesting depth of any value within this list.
+    ///
+    /// A bare value has depth 0; the values of `[1,2]` have depth 1.
+    pub fn depth(&self) -> usize {
+        match self {
+            NestedList::Value(_) => 0,
+            NestedList::List(items) => 1 + items.iter().map(Self::depth).max().unwrap_or_default(),
+        }
+    }
+
+    /// Get the element at `path`.
+    pub fn get(&self, path: &[usize]) -> Option<&NestedList<T>> {
+        path.iter()
+            .try_fold(self, |node, &idx| node.as_list()?.get(idx))
+    }
+
+    /// Get the element at `path` mutably.
+    ///
+    /// The element may be replaced wholesale, for example to replace a list with a value.
+    pub fn get_mut(&mut self, path: &[usize]) -> Option<&mut NestedList<T>> {
+        path.iter()
+            .try_fold(self, |node, &idx| node.as_list_mut()?.get_mut(idx))
+    }
+
+    /// Replace the element at `path`, returning the element previously there.
+    pub fn replace(&mut self, path: &[usize], element: NestedList<T>) -> Option<NestedList<T>> {
+        self.get_mut(path)
+            .map(|slot| std::mem::replace(slot, element))
+    }
+
+    /// Iterate over all values, depth-first, in the order they appear in the input.
+    pub fn values(&self) -> Values<'_, T> {
+        Values(Walk::new(self))
+    }
+
+    /// Iterate over all values, depth-first, along with the path to each.
+    ///
+    /// The length of each path is the depth of that value.
+    pub fn leaves(&self) -> Leaves<'_, T> {
+        Leaves(Walk::new(self))
+    }
+
+    /// Iterate mutably over all values, depth-first.
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        fn collect<'a, T>(node: &'a mut NestedList<T>, out: &mut Vec<&'a mut T>) {
+            match node {
+                NestedList::Value(value) => out.push(value),
+                NestedList::List(items) => {
+                    for item in items {
+                        collect(item, out);
+                    }
+                }
+            }
+        }
+
+        let mut out = Vec::new();
+        collect(self, &mut out);
+        out.into_iter()
+    }
+
+    /// Transform every value, preserving the structure of the list.
+    pub fn map<U>(self, mut f: impl FnMut(T) -> U) -> NestedList<U> {
+        fn map_inner<T, U>(node: NestedList<T>, f: &mut impl FnMut(T) -> U) -> NestedList<U> {
+            match node {
+                NestedList::Value(value) => NestedList::Value(f(value)),
+                NestedList::List(items) => {
+                    NestedList::List(items.into_iter().map(|item| map_inner(item, f)).collect())
+                }
+            }
+        }
+
+        map_inner(self, &mut f)
+    }
+}
+
+/// Depth-first traversal of the values of a [`NestedList`], tracking the path to each.
+struct Walk<'a, T> {
+    root: Option<&'a NestedList<T>>,
+    stack: Vec<Enumerate<slice::Iter<'a, NestedList<T>>>>,
+    // invariant: `path.len() == stack.len()`; `path[i]` is the index last taken from `stack[i]`
+    path: Vec<usize>,
+}
+
+impl<'a, T> Walk<'a, T> {
+    fn new(root: &'a NestedList<T>) -> Self {
+        Walk {
+            root: Some(root),
+            stack: Vec::new(),
+            path: Vec::new(),
+        }
+    }
+
+    /// Advance to the next value. Afterwards, `self.path` is the path to that value.
+    fn next_value(&mut self) -> Option<&'a T> {
+        loop {
+            let node = match self.root.take() {
+                Some(root) => root,
+                None => match self.stack.last_mut()?.next() {
+                    Some((idx, node)) => {
+                        *self.path.last_mut().expect("path.len() == stack.len()") = idx;
+                        node
+                    }
+                    None => {
+                        self.stack.pop();
+                        self.path.pop();
+                        continue;
+                    }
+                },
+            };
+            match node {
+                NestedList::Value(value) => return Some(value),
+                NestedList::List(items) => {
+                    self.stack.push(items.iter().enumerate());
+                    self.path.push(0);
+                }
+            }
+        }
+    }
+}
+
+/// Iterator over the values of a [`NestedList`], created by [`NestedList::values`].
+pub struct Values<'a, T>(Walk<'a, T>);
+
+impl<'a, T> Iterator for Values<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next_value()
+    }
+}
+
+/// Iterator over the values of a [`NestedList`] and their paths, created by [`NestedList::leaves`].
+pub struct Leaves<'a, T>(Walk<'a, T>);
+
+impl<'a, T> Iterator for Leaves<'a, T> {
+    type Item = (Vec<usize>, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.0.next_value()?;
+        Some((self.0.path.clone(), value))
+    }
+}
+
+impl<T: Ord> Ord for NestedList<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (NestedList::Value(left), NestedList::Value(right)) => left.cmp(right),
+            (NestedList::List(left), NestedList::List(right)) => left.cmp(right),
+            (NestedList::Value(_), NestedList::List(right)) => {
+                std::slice::from_ref(self).cmp(right.as_slice())
+            }
+            (NestedList::List(left), NestedList::Value(_)) => {
+                left.as_slice().cmp(std::slice::from_ref(other))
+            }
+        }
+    }
+}
+
+impl<T: Ord> PartialOrd for NestedList<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: PartialEq> PartialEq for NestedList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (NestedList::Value(left), NestedList::Value(right)) => left == right,
+            (NestedList::List(left), NestedList::List(right)) => left == right,
+            (NestedList::Value(_), NestedList::List(right)) => std::slice::from_ref(self) == right,
+            (NestedList::List(left), NestedList::Value(_)) => left == std::slice::from_ref(other),
+        }
+    }
+}
+
+impl<T: Eq> Eq for NestedList<T> {}
+
+impl<T: fmt::Display> fmt::Display for NestedList<T> {
+    /// Lists are written without whitespace, as in `[1,[2,3]]`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NestedList::Value(value) => write!(f, "{}", value),
+            NestedList::List(items) => {
+                write!(f, "[")?;
+                for (idx, item) in items.iter().enumerate() {
+                    if idx != 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+/// A [`NestedList`] could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum NestedListError {
+    #[error("column {column}: expected {expected}")]
+    Expected {
+        column: usize,
+        expected: &'static str,
+    },
+    #[error("unexpected end of input; {0} list(s) unclosed")]
+    Unclosed(usize),
+    #[error("column {column}: unexpected trailing input")]
+    TrailingInput { column: usize },
+    #[error("column {column}: parsing {text:?} as {type_name}: {message}")]
+    Value {
+        column: usize,
+        text: String,
+        type_name: &'static str,
+        message: String,
+    },
+}
+
+/// Recursive-descent parser over the characters of the input.
+struct Parser<'a> {
+    input: &'a str,
+    chars: std::iter::Peekable<Enumerate<std::str::CharIndices<'a>>>,
+    open: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn skip_whitespace(&mut self) {
+        while self
+            .chars
+            .peek()
+            .is_some_and(|(_, (_, ch))| ch.is_whitespace())
+        {
+            self.chars.next();
+        }
+    }
+
+    /// 1-based column of the next character, or one past the end.
+    fn column(&mut self) -> usize {
+        match self.chars.peek() {
+            Some((idx, _)) => idx + 1,
+            None => self.input.chars().count() + 1,
+        }
+    }
+
+    fn element<T>(&mut self) -> Result<NestedList<T>, NestedListError>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: fmt::Display,
+    {
+        self.skip_whitespace();
+        match self.chars.peek() {
+            None if self.open > 0 => Err(NestedListError::Unclosed(self.open)),
+            Some((_, (_, '['))) => {
+                self.chars.next();
+                self.open += 1;
+                self.list()
+            }
+            Some((_, (_, ',' | ']'))) | None => Err(NestedListError::Expected {
+                column: self.column(),
+                expected: "a value or `[`",
+            }),
+            Some(&(column_idx, (start, _))) => {
+                let mut end = start;
+                while let Some(&(_, (idx, ch))) = self.chars.peek() {
+                    if matches!(ch, '[' | ']' | ',') {
+                        break;
+                    }
+                    end = idx + ch.len_utf8();
+                    self.chars.next();
+                }
+                let text = self.input[start..end].trim();
+                text.parse()
+                    .map(NestedList::Value)
+                    .map_err(|err: <T as FromStr>::Err| NestedListError::Value {
+                        column: column_idx + 1,
+                        text: text.to_owned(),
+                        type_name: std::any::type_name::<T>(),
+                        message: err.to_string(),
+                    })
+            }
+        }
+    }
+
+    /// Parse the remainder of a list whose `[` has already been consumed.
+    fn list<T>(&mut self) -> Result<NestedList<T>, NestedListError>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: fmt::Display,
+    {
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.chars.next_if(|(_, (_, ch))| *ch == ']').is_some() {
+            self.open -= 1;
+            return Ok(NestedList::List(items));
+        }
+        loop {
+            items.push(self.element()?);
+            self.skip_whitespace();
+            match self.chars.next() {
+                Some((_, (_, ','))) => continue,
+                Some((_, (_, ']'))) => {
+                    self.open -= 1;
+                    return Ok(NestedList::List(items));
+                }
+                Some((column_idx, _)) => {
+                    return Err(NestedListError::Expected {
+                        column: column_idx + 1,
+                        expected: "`,` or `]`",
+                    })
+                }
+                None => return Err(NestedListError::Unclosed(self.open)),
+            }
+        }
+    }
+}
+
+impl<T> FromStr for NestedList<T>
+where
+    T: FromStr,
+    <T as FromStr>::Err: fmt::Display,
+{
+    type Err = NestedListError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            input: s,
+            chars: s.char_indices().enumerate().peekable(),
+            open: 0,
+        };
+        let list = parser.element()?;
+        parser.skip_whitespace();
+        if parser.chars.peek().is_some() {
+            return Err(NestedListError::TrailingInput {
+                column: parser.column(),
+            });
+        }
+        Ok(list)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> NestedList<u32> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for s in ["[]", "[[]]", "[1,[2,[3,[4,[5,6,7]]]],8,9]", "7"] {
+            assert_eq!(parse(s).to_string(), s);
+        }
+        assert_eq!(parse(" [ 1 , [ 2 ] ] ").to_string(), "[1,[2]]");
+    }
+
+    #[test]
+    fn test_packet_ordering() {
+        let pairs = [
+            ("[1,1,3,1,1]", "[1,1,5,1,1]", true),
+            ("[[1],[2,3,4]]", "[[1],4]", true),
+            ("[9]", "[[8,7,6]]", false),
+            ("[[4,4],4,4]", "[[4,4],4,4,4]", true),
+            ("[7,7,7,7]", "[7,7,7]", false),
+            ("[]", "[3]", true),
+            ("[[[]]]", "[[]]", false),
+            (
+                "[1,[2,[3,[4,[5,6,7]]]],8,9]",
+                "[1,[2,[3,[4,[5,6,0]]]],8,9]",
+                false,
+            ),
+        ];
+        for (left, right, ordered) in pairs {
+            assert_eq!(parse(left) < parse(right), ordered, "{} vs {}", left, right);
+        }
+    }
+
+    #[test]
+    fn test_mixed_equality() {
+        assert_eq!(parse("1"), parse("[[1]]"));
+        assert_ne!(parse("[1]"), parse("[1,1]"));
+    }
+
+    #[test]
+    fn test_leaves_and_depth() {
+        let list = parse("[[9,1],[[2,3],4]]");
+        assert_eq!(list.depth(), 3);
+        let leaves: Vec<_> = list.leaves().map(|(path, &v)| (path, v)).collect();
+        assert_eq!(
+            leaves,
+            vec![
+                (vec![0, 0], 9),
+                (vec![0, 1], 1),
+                (vec![1, 0, 0], 2),
+                (vec![1, 0, 1], 3),
+                (vec![1, 1], 4),
+            ]
+        );
+        for (path, value) in list.leaves() {
+            assert_eq!(list.get(&path).and_then(NestedList::as_value), Some(value));
+        }
+    }
+
+    #[test]
+    fn test_parse_input() {
+        let packets: Vec<NestedList<u32>> =
+            crate::input::parse_str("[1,[2]]\n[3]\n").unwrap().collect();
+        assert_eq!(packets.len(), 2);
+        let clusters: Vec<NestedList<u32>> =
+            crate::input::parse_newline_sep_str("[1,[2]]\n\n[3]\n")
+                .unwrap()
+                .collect();
+        assert_eq!(clusters, packets);
+    }
+
+    #[test]
+    fn test_mutation() {
+        let mut list = parse("[[1,2],3]");
+        for value in list.values_mut() {
+            *value *= 10;
+        }
+        let old = list.replace(&[0], NestedList::Value(0));
+        assert_eq!(old.unwrap().to_string(), "[10,20]");
+        assert_eq!(list.to_string(), "[0,30]");
+        assert!(list.get(&[5]).is_none());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            "[1,2".parse::<NestedList<u32>>(),
+            Err(NestedListError::Unclosed(1))
+        );
+        assert_eq!(
+            "[1,,2]".parse::<NestedList<u32>>(),
+            Err(NestedListError::Expected {
+                column: 4,
+                expected: "a value or `[`"
+            })
+        );
+        assert_eq!(
+            "[1]]".parse::<NestedList<u32>>(),
+            Err(NestedListError::TrailingInput { column: 4 })
+        );
+        assert!(matches!(
+            "[1,x]".parse::<NestedList<u32>>(),
+            Err(NestedListError::Value { column: 4, .. })
+        ));
+    }
+}
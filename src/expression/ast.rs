@@ -0,0 +1,61 @@
+use super::{EvalError, Operator};
+use num::{
+    traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub},
+    Zero,
+};
+use std::fmt;
+
+/// An expression tree, with the order of operations made explicit.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Ast<N> {
+    Number(N),
+    Binary {
+        operator: Operator,
+        lhs: Box<Ast<N>>,
+        rhs: Box<Ast<N>>,
+    },
+}
+
+impl<N> Ast<N> {
+    pub(crate) fn binary(operator: Operator, lhs: Ast<N>, rhs: Ast<N>) -> Ast<N> {
+        Ast::Binary {
+            operator,
+            lhs: Box::new(lhs),
+            rhs: Box::new(rhs),
+        }
+    }
+}
+
+impl<N> Ast<N>
+where
+    N: Clone + Zero + CheckedAdd + CheckedSub + CheckedMul + CheckedDiv,
+{
+    /// Compute the value of this expression.
+    pub fn evaluate(&self) -> Result<N, EvalError> {
+        match self {
+            Ast::Number(n) => Ok(n.clone()),
+            Ast::Binary { operator, lhs, rhs } => {
+                let lhs = lhs.evaluate()?;
+                let rhs = rhs.evaluate()?;
+                match operator {
+                    Operator::Add => lhs.checked_add(&rhs),
+                    Operator::Sub => lhs.checked_sub(&rhs),
+                    Operator::Mul => lhs.checked_mul(&rhs),
+                    Operator::Div if rhs.is_zero() => return Err(EvalError::DivisionByZero),
+                    Operator::Div => lhs.checked_div(&rhs),
+                }
+                .ok_or(EvalError::Overflow(*operator))
+            }
+        }
+    }
+}
+
+impl<N: fmt::Display> fmt::Display for Ast<N> {
+    /// Every binary operation is parenthesized, so the order of operations is unambiguous.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Ast::Number(n) => write!(f, "{}", n),
+            Ast::Binary { operator, lhs, rhs } => write!(f, "({} {} {})", lhs, operator, rhs),
+        }
+    }
+}
@@ -0,0 +1,388 @@
+//! Arithmetic expressions with configurable operator precedence.
+//!
+//! Several puzzles ask for infix arithmetic to be evaluated under unusual rules: every
+//! operator with equal precedence, or addition binding more tightly than multiplication.
+//! An [`Expression`] is parsed once, without reference to any precedence rules; a
+//! [`Precedence`] table is supplied when it is converted into an [`Ast`] or evaluated.
+//!
+//! Expressions consist of integer literals, the binary operators `+`, `-`, `*`, and `/`,
+//! and parentheses. Whitespace between tokens is ignored. A `-` immediately followed by a
+//! digit, in a position where an operand is expected, begins a negative literal.
+//!
+//! `Expression` implements `FromStr`, so it plugs directly into [`crate::input::parse`].
+//!
+//! ## Example
+//!
+//! ```
+//! use aoclib::expression::{Expression, Precedence};
+//!
+//! let expr: Expression = "1 + 2 * 3 + 4 * 5 + 6".parse().unwrap();
+//! assert_eq!(expr.evaluate(&Precedence::standard()).unwrap(), 33);
+//! assert_eq!(expr.evaluate(&Precedence::equal()).unwrap(), 71);
+//! assert_eq!(expr.evaluate(&Precedence::addition_first()).unwrap(), 231);
+//!
+//! let ast = expr.to_ast(&Precedence::addition_first());
+//! assert_eq!(ast.to_string(), "(((1 + 2) * (3 + 4)) * (5 + 6))");
+//! ```
+
+mod ast;
+mod precedence;
+
+pub use ast::Ast;
+pub use precedence::{Associativity, Binding, Precedence};
+
+use num::{
+    traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub},
+    Zero,
+};
+use std::{fmt::Display, str::FromStr};
+
+/// A binary arithmetic operator.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, parse_display::Display, parse_display::FromStr,
+)]
+pub enum Operator {
+    #[display("+")]
+    Add,
+    #[display("-")]
+    Sub,
+    #[display("*")]
+    Mul,
+    #[display("/")]
+    Div,
+}
+
+impl Operator {
+    pub(crate) const COUNT: usize = 4;
+
+    fn from_char(ch: char) -> Option<Operator> {
+        match ch {
+            '+' => Some(Operator::Add),
+            '-' => Some(Operator::Sub),
+            '*' => Some(Operator::Mul),
+            '/' => Some(Operator::Div),
+            _ => None,
+        }
+    }
+}
+
+/// A single lexical element of an expression.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Token<N> {
+    Number(N),
+    Operator(Operator),
+    OpenParen,
+    CloseParen,
+}
+
+/// A token, with the 1-based column at which it begins.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Spanned<N> {
+    pub column: usize,
+    pub token: Token<N>,
+}
+
+/// An expression could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ParseError {
+    #[error("column {column}: unexpected character {ch:?}")]
+    UnexpectedChar { column: usize, ch: char },
+    #[error("column {column}: parsing {text:?}: {message}")]
+    Number {
+        column: usize,
+        text: String,
+        message: String,
+    },
+    #[error("column {column}: expected {expected}")]
+    Expected {
+        column: usize,
+        expected: &'static str,
+    },
+    #[error("column {column}: unmatched `)`")]
+    UnmatchedClose { column: usize },
+    #[error("column {column}: unmatched `(`")]
+    UnmatchedOpen { column: usize },
+}
+
+/// An expression could not be evaluated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum EvalError {
+    #[error("division by zero")]
+    DivisionByZero,
+    #[error("overflow applying `{0}`")]
+    Overflow(Operator),
+}
+
+/// Split an expression into tokens.
+///
+/// This checks only that each token is well-formed, not that the tokens form a valid expression.
+pub fn tokenize<N>(s: &str) -> Result<Vec<Spanned<N>>, ParseError>
+where
+    N: FromStr,
+    <N as FromStr>::Err: Display,
+{
+    let mut tokens: Vec<Spanned<N>> = Vec::new();
+    let mut chars = s.char_indices().enumerate().peekable();
+    while let Some((column_idx, (byte_idx, ch))) = chars.next() {
+        let column = column_idx + 1;
+        let expects_operand = matches!(
+            tokens.last(),
+            None | Some(Spanned {
+                token: Token::Operator(_) | Token::OpenParen,
+                ..
+            })
+        );
+        let starts_negative = ch == '-'
+            && expects_operand
+            && chars
+                .peek()
+                .is_some_and(|(_, (_, next))| next.is_ascii_digit());
+
+        let token = if ch.is_whitespace() {
+            continue;
+        } else if ch.is_ascii_digit() || starts_negative {
+            let mut end = byte_idx + ch.len_utf8();
+            while let Some((_, (idx, next))) = chars.peek() {
+                if !next.is_ascii_digit() {
+                    break;
+                }
+                end = idx + next.len_utf8();
+                chars.next();
+            }
+            let text = &s[byte_idx..end];
+            Token::Number(
+                text.parse()
+                    .map_err(|err: <N as FromStr>::Err| ParseError::Number {
+                        column,
+                        text: text.to_owned(),
+                        message: err.to_string(),
+                    })?,
+            )
+        } else if let Some(operator) = Operator::from_char(ch) {
+            Token::Operator(operator)
+        } else if ch == '(' {
+            Token::OpenParen
+        } else if ch == ')' {
+            Token::CloseParen
+        } else {
+            return Err(ParseError::UnexpectedChar { column, ch });
+        };
+        tokens.push(Spanned { column, token });
+    }
+    Ok(tokens)
+}
+
+/// A syntactically valid arithmetic expression.
+///
+/// Parsing an expression does not depend on operator precedence; supply a [`Precedence`]
+/// table to [`to_ast`][Self::to_ast] or [`evaluate`][Self::evaluate].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Expression<N = i64> {
+    tokens: Vec<Token<N>>,
+}
+
+impl<N> Expression<N> {
+    /// The tokens of this expression, in input order.
+    pub fn tokens(&self) -> &[Token<N>] {
+        &self.tokens
+    }
+}
+
+impl<N: Clone> Expression<N> {
+    /// Build an expression tree, applying operators according to `precedence`.
+    pub fn to_ast(&self, precedence: &Precedence) -> Ast<N> {
+        let mut position = 0;
+        let ast = self.climb(precedence, &mut position, 0);
+        debug_assert_eq!(position, self.tokens.len(), "all tokens consumed");
+        ast
+    }
+
+    /// Parse a primary operand: a number or a parenthesized subexpression.
+    fn primary(&self, precedence: &Precedence, position: &mut usize) -> Ast<N> {
+        let token = &self.tokens[*position];
+        *position += 1;
+        match token {
+            Token::Number(n) => Ast::Number(n.clone()),
+            Token::OpenParen => {
+                let inner = self.climb(precedence, position, 0);
+                debug_assert!(matches!(self.tokens[*position], Token::CloseParen));
+                *position += 1;
+                inner
+            }
+            _ => unreachable!("expression was validated at parse time"),
+        }
+    }
+
+    /// Precedence climbing: parse operations whose precedence is at least `min_precedence`.
+    fn climb(&self, precedence: &Precedence, position: &mut usize, min_precedence: u16) -> Ast<N> {
+        let mut lhs = self.primary(precedence, position);
+        while let Some(Token::Operator(operator)) = self.tokens.get(*position) {
+            let binding = precedence.binding(*operator);
+            // widened, so that incrementing the highest precedence cannot saturate
+            let operator_precedence = u16::from(binding.precedence);
+            if operator_precedence < min_precedence {
+                break;
+            }
+            *position += 1;
+            let next_min = match binding.associativity {
+                Associativity::Left => operator_precedence + 1,
+                Associativity::Right => operator_precedence,
+            };
+            let rhs = self.climb(precedence, position, next_min);
+            lhs = Ast::binary(*operator, lhs, rhs);
+        }
+        lhs
+    }
+}
+
+impl<N> Expression<N>
+where
+    N: Clone + Zero + CheckedAdd + CheckedSub + CheckedMul + CheckedDiv,
+{
+    /// Compute the value of this expression, applying operators according to `precedence`.
+    pub fn evaluate(&self, precedence: &Precedence) -> Result<N, EvalError> {
+        self.to_ast(precedence).evaluate()
+    }
+}
+
+impl<N> FromStr for Expression<N>
+where
+    N: FromStr,
+    <N as FromStr>::Err: Display,
+{
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens = tokenize(s)?;
+
+        // an expression alternates between operands and operators
+        let mut expects_operand = true;
+        let mut open_parens = Vec::new();
+        for Spanned { column, token } in &tokens {
+            let column = *column;
+            match (expects_operand, token) {
+                (true, Token::Number(_)) => expects_operand = false,
+                (true, Token::OpenParen) => open_parens.push(column),
+                (false, Token::Operator(_)) => expects_operand = true,
+                (false, Token::CloseParen) => {
+                    if open_parens.pop().is_none() {
+                        return Err(ParseError::UnmatchedClose { column });
+                    }
+                }
+                (true, _) => {
+                    return Err(ParseError::Expected {
+                        column,
+                        expected: "a number or `(`",
+                    })
+                }
+                (false, _) => {
+                    return Err(ParseError::Expected {
+                        column,
+                        expected: "an operator or `)`",
+                    })
+                }
+            }
+        }
+        if expects_operand {
+            return Err(ParseError::Expected {
+                column: s.chars().count() + 1,
+                expected: "a number or `(`",
+            });
+        }
+        if let Some(column) = open_parens.pop() {
+            return Err(ParseError::UnmatchedOpen { column });
+        }
+
+        Ok(Expression {
+            tokens: tokens.into_iter().map(|spanned| spanned.token).collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(s: &str, precedence: Precedence) -> i64 {
+        s.parse::<Expression>()
+            .unwrap()
+            .evaluate(&precedence)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_equal_precedence_examples() {
+        for (expr, expect) in [
+            ("2 * 3 + (4 * 5)", 26),
+            ("5 + (8 * 3 + 9 + 3 * 4 * 3)", 437),
+            ("5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))", 12240),
+            ("((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2", 13632),
+        ] {
+            assert_eq!(eval(expr, Precedence::equal()), expect, "{}", expr);
+        }
+    }
+
+    #[test]
+    fn test_addition_first_examples() {
+        for (expr, expect) in [
+            ("2 * 3 + (4 * 5)", 46),
+            ("5 + (8 * 3 + 9 + 3 * 4 * 3)", 1445),
+            ("5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))", 669060),
+            ("((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2", 23340),
+        ] {
+            assert_eq!(eval(expr, Precedence::addition_first()), expect, "{}", expr);
+        }
+    }
+
+    #[test]
+    fn test_associativity() {
+        assert_eq!(eval("10 - 4 - 3", Precedence::standard()), 3);
+        let right = Precedence::standard().with(Operator::Sub, Binding::right(1));
+        assert_eq!(eval("10 - 4 - 3", right), 9);
+        // the highest precedence is still left-associative
+        let highest = Precedence::standard().with(Operator::Sub, Binding::left(u8::MAX));
+        assert_eq!(eval("10 - 4 - 3", highest), 3);
+    }
+
+    #[test]
+    fn test_negative_literals() {
+        assert_eq!(eval("-2 * (-3 - -1)", Precedence::standard()), 4);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            "1 + ".parse::<Expression>(),
+            Err(ParseError::Expected {
+                column: 5,
+                expected: "a number or `(`"
+            })
+        );
+        assert_eq!(
+            "(1 + 2".parse::<Expression>(),
+            Err(ParseError::UnmatchedOpen { column: 1 })
+        );
+        assert_eq!(
+            "1 + 2)".parse::<Expression>(),
+            Err(ParseError::UnmatchedClose { column: 6 })
+        );
+        assert_eq!(
+            "1 ^ 2".parse::<Expression>(),
+            Err(ParseError::UnexpectedChar { column: 3, ch: '^' })
+        );
+    }
+
+    #[test]
+    fn test_eval_errors() {
+        let expr: Expression<u8> = "200 + 100".parse().unwrap();
+        assert_eq!(
+            expr.evaluate(&Precedence::standard()),
+            Err(EvalError::Overflow(Operator::Add))
+        );
+        let expr: Expression = "1 / (2 - 2)".parse().unwrap();
+        assert_eq!(
+            expr.evaluate(&Precedence::standard()),
+            Err(EvalError::DivisionByZero)
+        );
+    }
+}
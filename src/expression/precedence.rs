@@ -0,0 +1,96 @@
+use super::Operator;
+
+/// The order in which chained binary operators of equal precedence are applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Associativity {
+    /// `a - b - c == (a - b) - c`
+    Left,
+    /// `a - b - c == a - (b - c)`
+    Right,
+}
+
+/// How tightly an operator binds its operands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Binding {
+    /// Operators with higher precedence are applied first.
+    pub precedence: u8,
+    pub associativity: Associativity,
+}
+
+impl Binding {
+    pub const fn left(precedence: u8) -> Binding {
+        Binding {
+            precedence,
+            associativity: Associativity::Left,
+        }
+    }
+
+    pub const fn right(precedence: u8) -> Binding {
+        Binding {
+            precedence,
+            associativity: Associativity::Right,
+        }
+    }
+}
+
+/// A table assigning a precedence and associativity to each [`Operator`].
+///
+/// The default table is [`Precedence::standard`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Precedence {
+    bindings: [Binding; Operator::COUNT],
+}
+
+impl Default for Precedence {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+impl Precedence {
+    /// Conventional arithmetic: multiplication and division before addition and subtraction.
+    ///
+    /// All operators are left-associative.
+    pub const fn standard() -> Precedence {
+        Precedence {
+            bindings: [
+                Binding::left(1),
+                Binding::left(1),
+                Binding::left(2),
+                Binding::left(2),
+            ],
+        }
+    }
+
+    /// All operators have equal precedence, so they are applied strictly left to right.
+    pub const fn equal() -> Precedence {
+        Precedence {
+            bindings: [Binding::left(1); Operator::COUNT],
+        }
+    }
+
+    /// Addition and subtraction before multiplication and division.
+    ///
+    /// All operators are left-associative.
+    pub const fn addition_first() -> Precedence {
+        Precedence {
+            bindings: [
+                Binding::left(2),
+                Binding::left(2),
+                Binding::left(1),
+                Binding::left(1),
+            ],
+        }
+    }
+
+    /// Replace the binding of a single operator.
+    pub const fn with(mut self, operator: Operator, binding: Binding) -> Precedence {
+        self.bindings[operator as usize] = binding;
+        self
+    }
+
+    /// Get the binding of an operator.
+    pub const fn binding(&self, operator: Operator) -> Binding {
+        self.bindings[operator as usize]
+    }
+}
@@ -0,0 +1,444 @@
+//! Cellular automata over points in any number of dimensions.
+//!
+//! An [`Automaton`] tracks only its active cells, so it can grow without limit in any direction.
+//! Each generation, every cell which is active or adjacent to an active cell is offered to a
+//! [`Rule`], along with the number of its active neighbors, to decide whether it is active next.
+//! Neighbors are defined by [`PointTrait::adjacent`].
+//!
+//! Because inactive cells with no active neighbors are never considered, rules which activate
+//! isolated cells (`B0`) have no effect.
+//!
+//! ## Example
+//!
+//! ```
+//! # use aoclib::geometry::{automaton::{Automaton, LifeLike}, Point};
+//! // a glider travels one cell diagonally every four generations
+//! let glider = [(1, 2), (2, 1), (0, 0), (1, 0), (2, 0)].map(|(x, y)| Point::new(x, y));
+//! let mut automaton = Automaton::new(LifeLike::conway(), glider);
+//! automaton.run(4);
+//! assert_eq!(automaton.generation(), 4);
+//! assert!(glider
+//!     .iter()
+//!     .all(|&cell| automaton.is_active(cell + Point::new(1, -1))));
+//! ```
+
+use super::{map::tile::Bool, point::PointTrait, Map, Point};
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+    str::FromStr,
+};
+
+/// Decides whether each cell of an [`Automaton`] is active in the next generation.
+///
+/// Any closure `Fn(P, bool, usize) -> bool` is a rule. It receives the cell, whether it is
+/// currently active, and how many of its neighbors are currently active.
+pub trait Rule<P> {
+    /// `true` if `cell` should be active in the next generation.
+    fn next(&self, cell: P, active: bool, active_neighbors: usize) -> bool;
+}
+
+impl<P, F> Rule<P> for F
+where
+    F: Fn(P, bool, usize) -> bool,
+{
+    fn next(&self, cell: P, active: bool, active_neighbors: usize) -> bool {
+        self(cell, active, active_neighbors)
+    }
+}
+
+/// A rule which depends only on the number of active neighbors.
+///
+/// Inactive cells become active when their count of active neighbors is among the birth counts.
+/// Active cells stay active when their count is among the survival counts.
+///
+/// This parses from and displays as the usual notation, for example `B3/S23` for Conway's
+/// game of life.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LifeLike {
+    // bit `n` is set when `n` is a member
+    birth: u128,
+    survival: u128,
+}
+
+impl LifeLike {
+    /// Largest neighbor count which can be expressed in a rule.
+    pub const MAX_NEIGHBORS: usize = 127;
+
+    /// Create a rule from its birth and survival counts.
+    ///
+    /// Counts greater than [`MAX_NEIGHBORS`][Self::MAX_NEIGHBORS] are ignored.
+    pub fn new(
+        birth: impl IntoIterator<Item = usize>,
+        survival: impl IntoIterator<Item = usize>,
+    ) -> LifeLike {
+        let mask = |counts: &mut dyn Iterator<Item = usize>| {
+            counts
+                .filter(|&count| count <= Self::MAX_NEIGHBORS)
+                .fold(0, |mask, count| mask | 1 << count)
+        };
+        LifeLike {
+            birth: mask(&mut birth.into_iter()),
+            survival: mask(&mut survival.into_iter()),
+        }
+    }
+
+    /// Conway's game of life: `B3/S23`.
+    pub fn conway() -> LifeLike {
+        LifeLike::new([3], [2, 3])
+    }
+
+    /// `true` if an inactive cell with this many active neighbors becomes active.
+    pub fn births(&self, active_neighbors: usize) -> bool {
+        active_neighbors <= Self::MAX_NEIGHBORS && self.birth & 1 << active_neighbors != 0
+    }
+
+    /// `true` if an active cell with this many active neighbors stays active.
+    pub fn survives(&self, active_neighbors: usize) -> bool {
+        active_neighbors <= Self::MAX_NEIGHBORS && self.survival & 1 << active_neighbors != 0
+    }
+}
+
+impl<P> Rule<P> for LifeLike {
+    fn next(&self, _cell: P, active: bool, active_neighbors: usize) -> bool {
+        if active {
+            self.survives(active_neighbors)
+        } else {
+            self.births(active_neighbors)
+        }
+    }
+}
+
+impl std::fmt::Display for LifeLike {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let write_counts = |f: &mut std::fmt::Formatter<'_>, mask: u128| {
+            (0..=Self::MAX_NEIGHBORS)
+                .filter(|&count| mask & 1 << count != 0)
+                .try_for_each(|count| write!(f, "{}", count))
+        };
+        write!(f, "B")?;
+        write_counts(f, self.birth)?;
+        write!(f, "/S")?;
+        write_counts(f, self.survival)
+    }
+}
+
+/// A [`LifeLike`] rule could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum LifeLikeParseError {
+    #[error("expected a rule of the form `B3/S23`")]
+    Malformed,
+    #[error("`{0}` is not a neighbor count")]
+    InvalidCount(char),
+}
+
+impl FromStr for LifeLike {
+    type Err = LifeLikeParseError;
+
+    /// Parse a rule in `B3/S23` notation.
+    ///
+    /// Each digit is a separate count, so this can only express counts up to 9.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (birth, survival) = s
+            .trim()
+            .split_once('/')
+            .ok_or(LifeLikeParseError::Malformed)?;
+        let counts = |part: &str, prefix: char| -> Result<Vec<usize>, LifeLikeParseError> {
+            let part = part
+                .strip_prefix(prefix)
+                .or_else(|| part.strip_prefix(prefix.to_ascii_lowercase()))
+                .ok_or(LifeLikeParseError::Malformed)?;
+            part.chars()
+                .map(|c| {
+                    c.to_digit(10)
+                        .map(|digit| digit as usize)
+                        .ok_or(LifeLikeParseError::InvalidCount(c))
+                })
+                .collect()
+        };
+        Ok(LifeLike::new(counts(birth, 'B')?, counts(survival, 'S')?))
+    }
+}
+
+/// A cellular automaton whose cells are points of type `P`.
+///
+/// Only active cells are stored. When bounds are set, cells outside them are never active.
+#[derive(Debug, Clone)]
+pub struct Automaton<P, R> {
+    active: HashSet<P>,
+    rule: R,
+    bounds: Option<(P, P)>,
+    generation: usize,
+}
+
+impl<P, R> Automaton<P, R>
+where
+    P: 'static + PointTrait + Hash,
+    R: Rule<P>,
+{
+    /// Create an unbounded automaton in which the given cells are active.
+    pub fn new(rule: R, active: impl IntoIterator<Item = P>) -> Automaton<P, R> {
+        Automaton {
+            active: active.into_iter().collect(),
+            rule,
+            bounds: None,
+            generation: 0,
+        }
+    }
+
+    /// Confine this automaton to the cells inclusively bounded by `min` and `max`.
+    ///
+    /// Active cells outside the bounds are deactivated. Cells outside the bounds still exist,
+    /// but are always inactive, so they never count as active neighbors.
+    pub fn with_bounds(mut self, min: P, max: P) -> Automaton<P, R> {
+        let (min, max) = (min.boundary_min(max), min.boundary_max(max));
+        self.active
+            .retain(|&cell| min.boundary_min(cell) == min && max.boundary_max(cell) == max);
+        self.bounds = Some((min, max));
+        self
+    }
+
+    /// The inclusive bounds of this automaton, if it is bounded.
+    pub fn bounds(&self) -> Option<(P, P)> {
+        self.bounds
+    }
+
+    /// `true` if `cell` lies within this automaton's bounds, or it is unbounded.
+    pub fn in_bounds(&self, cell: P) -> bool {
+        self.bounds
+            .is_none_or(|(min, max)| min.boundary_min(cell) == min && max.boundary_max(cell) == max)
+    }
+
+    /// Number of generations which have elapsed.
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// The currently active cells.
+    pub fn active(&self) -> &HashSet<P> {
+        &self.active
+    }
+
+    /// Take ownership of the currently active cells.
+    pub fn into_active(self) -> HashSet<P> {
+        self.active
+    }
+
+    /// `true` if `cell` is currently active.
+    pub fn is_active(&self, cell: P) -> bool {
+        self.active.contains(&cell)
+    }
+
+    /// Number of currently active cells.
+    pub fn len(&self) -> usize {
+        self.active.len()
+    }
+
+    /// `true` when no cells are active.
+    pub fn is_empty(&self) -> bool {
+        self.active.is_empty()
+    }
+
+    /// Inclusive bounds of the smallest box containing every active cell, or `None` if no
+    /// cells are active.
+    pub fn extent(&self) -> Option<(P, P)> {
+        let mut cells = self.active.iter().copied();
+        let first = cells.next()?;
+        Some(cells.fold((first, first), |(min, max), cell| {
+            (min.boundary_min(cell), max.boundary_max(cell))
+        }))
+    }
+
+    /// Iterate over every cell within the bounds, if any, or else the extent of the active
+    /// cells, along with whether each is active.
+    pub fn cells(&self) -> impl '_ + Iterator<Item = (P, bool)> {
+        self.bounds
+            .or_else(|| self.extent())
+            .into_iter()
+            .flat_map(|(min, max)| P::inclusive_range(min, max))
+            .map(move |cell| (cell, self.is_active(cell)))
+    }
+
+    /// Advance by one generation.
+    ///
+    /// Returns `true` if any cell changed state.
+    pub fn step(&mut self) -> bool {
+        let mut neighbor_counts: HashMap<P, usize> = HashMap::with_capacity(self.active.len() * 4);
+        for &cell in &self.active {
+            for neighbor in cell.adjacent() {
+                *neighbor_counts.entry(neighbor).or_default() += 1;
+            }
+        }
+        // active cells with no active neighbors are still candidates
+        for &cell in &self.active {
+            neighbor_counts.entry(cell).or_default();
+        }
+
+        let next: HashSet<P> = neighbor_counts
+            .into_iter()
+            .filter(|&(cell, count)| {
+                self.in_bounds(cell) && self.rule.next(cell, self.active.contains(&cell), count)
+            })
+            .map(|(cell, _)| cell)
+            .collect();
+
+        self.generation += 1;
+        let changed = next != self.active;
+        self.active = next;
+        changed
+    }
+
+    /// Advance by the given number of generations.
+    pub fn run(&mut self, generations: usize) {
+        for _ in 0..generations {
+            self.step();
+        }
+    }
+
+    /// Advance until a generation in which no cell changes, up to `max_generations` more
+    /// generations.
+    ///
+    /// Returns the generation at which the automaton became stable, or `None` if it had not
+    /// stabilized by the limit. The automaton is left in its final state either way.
+    ///
+    /// Automata which oscillate never become stable; see [`crate::cycle`] for those.
+    pub fn run_until_stable(&mut self, max_generations: usize) -> Option<usize> {
+        for _ in 0..max_generations {
+            if !self.step() {
+                return Some(self.generation - 1);
+            }
+        }
+        None
+    }
+}
+
+impl<R: Rule<Point>> Automaton<Point, R> {
+    /// Create an automaton bounded by a map, in which tiles matching `is_active` are active.
+    pub fn from_map<Tile>(
+        rule: R,
+        map: &Map<Tile>,
+        is_active: impl Fn(&Tile) -> bool,
+    ) -> Automaton<Point, R> {
+        Automaton::new(
+            rule,
+            map.iter()
+                .filter(|(_, tile)| is_active(tile))
+                .map(|(point, _)| point),
+        )
+        .with_bounds(map.bottom_left(), map.top_right())
+    }
+
+    /// Render the active cells into a map.
+    ///
+    /// The map covers the automaton's bounds, if any, or else the extent of its active cells.
+    pub fn to_map(&self) -> Map<Bool> {
+        let (min, max) = match self.bounds.or_else(|| self.extent()) {
+            Some(bounds) => bounds,
+            None => return Map::new(0, 0),
+        };
+        Map::procedural_offset(
+            min,
+            (max.x - min.x + 1) as usize,
+            (max.y - min.y + 1) as usize,
+            |point| Bool::from(self.is_active(point)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::{vector3::Vector3, vector4::Vector4};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_parse_life_like() {
+        let rule: LifeLike = "B3/S23".parse().unwrap();
+        assert_eq!(rule, LifeLike::conway());
+        assert_eq!(rule.to_string(), "B3/S23");
+        assert_eq!(
+            "b36/s23".parse::<LifeLike>().unwrap().to_string(),
+            "B36/S23"
+        );
+        assert_eq!(
+            "B/S".parse::<LifeLike>().unwrap(),
+            LifeLike::new(None, None)
+        );
+        assert_eq!(
+            "B3S23".parse::<LifeLike>(),
+            Err(LifeLikeParseError::Malformed)
+        );
+        assert_eq!(
+            "B3/S2x".parse::<LifeLike>(),
+            Err(LifeLikeParseError::InvalidCount('x'))
+        );
+    }
+
+    #[test]
+    fn test_blinker_is_not_stable() {
+        let blinker = [(0, 0), (1, 0), (2, 0)].map(|(x, y)| Point::new(x, y));
+        let mut automaton = Automaton::new(LifeLike::conway(), blinker);
+        assert!(automaton.step());
+        assert_eq!(
+            automaton.extent(),
+            Some((Point::new(1, -1), Point::new(1, 1)))
+        );
+        assert_eq!(automaton.run_until_stable(10), None);
+        assert_eq!(automaton.generation(), 11);
+
+        // a block never changes
+        let block = [(0, 0), (1, 0), (0, 1), (1, 1)].map(|(x, y)| Point::new(x, y));
+        let mut automaton = Automaton::new(LifeLike::conway(), block);
+        assert_eq!(automaton.run_until_stable(10), Some(0));
+        assert_eq!(automaton.cells().count(), 4);
+        assert!(automaton.cells().all(|(_, active)| active));
+    }
+
+    #[test]
+    fn test_bounded_lights() {
+        // 2015 day 18 example
+        let map = <Map<Bool> as TryFrom<&str>>::try_from(
+            ".#.#.#\n...##.\n#....#\n..#...\n#.#..#\n####..\n",
+        )
+        .unwrap();
+
+        let mut automaton = Automaton::from_map(LifeLike::conway(), &map, |&tile| tile == true);
+        automaton.run(4);
+        assert_eq!(automaton.len(), 4);
+        assert_eq!(automaton.to_map().width(), 6);
+
+        // the corners are stuck on
+        let corners = [
+            map.bottom_left(),
+            map.top_left(),
+            map.bottom_right(),
+            map.top_right(),
+        ];
+        let stuck = |cell: Point, active, neighbors| {
+            corners.contains(&cell) || LifeLike::conway().next(cell, active, neighbors)
+        };
+        let lit = map
+            .iter()
+            .filter(|(_, &tile)| tile == true)
+            .map(|(point, _)| point);
+        let mut automaton = Automaton::new(stuck, lit.chain(corners))
+            .with_bounds(map.bottom_left(), map.top_right());
+        automaton.run(5);
+        assert_eq!(automaton.len(), 17);
+    }
+
+    #[test]
+    fn test_higher_dimensions() {
+        // 2020 day 17 example
+        let initial = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+        let rule = LifeLike::conway();
+
+        let mut automaton = Automaton::new(rule, initial.map(|(x, y)| Vector3::new(x, y, 0)));
+        automaton.run(6);
+        assert_eq!(automaton.len(), 112);
+
+        let mut automaton = Automaton::new(rule, initial.map(|(x, y)| Vector4::new(x, y, 0, 0)));
+        automaton.run(6);
+        assert_eq!(automaton.len(), 848);
+    }
+}
@@ -4,6 +4,11 @@ use crate::geometry::Point;
 // https://doc.rust-lang.org/std/collections/binary_heap/#examples
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub(crate) struct AStarNode {
+    /// Best guess as to the total cost of a path through this node (the f-score).
+    ///
+    /// For an uninformed search such as Dijkstra's algorithm, this is equal to `cost`.
+    pub estimate: u32,
+    /// Cost of the path from the start to this node (the g-score).
     pub cost: u32,
     pub position: Point,
 }
@@ -13,12 +18,14 @@ pub(crate) struct AStarNode {
 // instead of a max-heap.
 impl Ord for AStarNode {
     fn cmp(&self, other: &AStarNode) -> std::cmp::Ordering {
-        // Notice that the we flip the ordering on costs.
+        // Notice that the we flip the ordering on estimates.
+        // Among equal estimates, prefer the node furthest along its path: it is closest to the goal.
         // In case of a tie we compare positions - this step is necessary
         // to make implementations of `PartialEq` and `Ord` consistent.
         other
-            .cost
-            .cmp(&self.cost)
+            .estimate
+            .cmp(&self.estimate)
+            .then_with(|| self.cost.cmp(&other.cost))
             .then_with(|| self.position.cmp(&other.position))
     }
 }
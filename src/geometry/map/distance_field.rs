@@ -0,0 +1,284 @@
+use super::{ContextInto, Map, Neighborhood, Traversable};
+use crate::geometry::Point;
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+/// Which source is nearest to a tile of a [`DistanceField`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Owner {
+    /// The source at this index among the sources is strictly nearer than any other.
+    Source(usize),
+    /// Two or more sources are equally near.
+    Tie,
+}
+
+impl Owner {
+    /// Combine the owners of two equally short paths.
+    fn merge(self, other: Owner) -> Owner {
+        match (self, other) {
+            (Owner::Source(a), Owner::Source(b)) if a == b => Owner::Source(a),
+            _ => Owner::Tie,
+        }
+    }
+}
+
+/// The distance from every tile of a [`Map`] to the nearest of a set of sources.
+///
+/// Created by [`Map::distance_field`] and its variants.
+///
+/// ## Example
+///
+/// ```
+/// # use aoclib::geometry::{map::{Owner, Traversable}, Map, Point};
+/// let map = Map::procedural(5, 1, |_| Traversable::Free);
+/// let field = map.distance_field([Point::new(0, 0), Point::new(3, 0)]);
+/// assert_eq!(field.distance(Point::new(1, 0)), Some(1));
+/// assert_eq!(field.distance(Point::new(4, 0)), Some(1));
+/// assert_eq!(field.owner(Point::new(1, 0)), Some(Owner::Source(0)));
+/// assert_eq!(field.owner(Point::new(2, 0)), Some(Owner::Source(1)));
+///
+/// let field = map.distance_field([Point::new(0, 0), Point::new(4, 0)]);
+/// assert_eq!(field.owner(Point::new(2, 0)), Some(Owner::Tie));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DistanceField {
+    distances: Map<Option<u32>>,
+    owners: Map<Option<Owner>>,
+}
+
+impl DistanceField {
+    /// Distance from a point to its nearest source, or `None` if no source can reach it.
+    pub fn distance(&self, point: Point) -> Option<u32> {
+        self.distances.make_resolve()(point).and_then(|point| self.distances[point])
+    }
+
+    /// The source nearest to a point, or `None` if no source can reach it.
+    pub fn owner(&self, point: Point) -> Option<Owner> {
+        self.owners.make_resolve()(point).and_then(|point| self.owners[point])
+    }
+
+    /// Distance from every point to its nearest source.
+    pub fn distances(&self) -> &Map<Option<u32>> {
+        &self.distances
+    }
+
+    /// The source nearest to every point.
+    pub fn owners(&self) -> &Map<Option<Owner>> {
+        &self.owners
+    }
+
+    /// Discard ownership, keeping only the distances.
+    pub fn into_distances(self) -> Map<Option<u32>> {
+        self.distances
+    }
+}
+
+impl<Tile> Map<Tile>
+where
+    Tile: Clone + ContextInto<Traversable, Context = ()>,
+{
+    /// Compute the distance from every tile to the nearest of the sources, and which source
+    /// that is.
+    ///
+    /// See [`distance_field_ctx_with`][Self::distance_field_ctx_with].
+    pub fn distance_field(&self, sources: impl IntoIterator<Item = Point>) -> DistanceField {
+        self.distance_field_ctx(&(), sources)
+    }
+
+    /// Compute the distance from every tile to the nearest of the sources, and which source
+    /// that is, moving between neighbors as defined by `neighborhood`.
+    ///
+    /// See [`distance_field_ctx_with`][Self::distance_field_ctx_with].
+    pub fn distance_field_with(
+        &self,
+        neighborhood: &Neighborhood,
+        sources: impl IntoIterator<Item = Point>,
+    ) -> DistanceField {
+        self.distance_field_ctx_with(&(), neighborhood, sources)
+    }
+}
+
+impl<Tile: Clone + ContextInto<Traversable>> Map<Tile> {
+    /// Compute the distance from every tile to the nearest of the sources, and which source
+    /// that is.
+    ///
+    /// See [`distance_field_ctx_with`][Self::distance_field_ctx_with].
+    pub fn distance_field_ctx(
+        &self,
+        context: &<Tile as ContextInto<Traversable>>::Context,
+        sources: impl IntoIterator<Item = Point>,
+    ) -> DistanceField {
+        self.distance_field_ctx_with(context, &Neighborhood::orthogonal(), sources)
+    }
+
+    /// Compute the distance from every tile to the nearest of the sources, and which source
+    /// that is, moving between neighbors as defined by `neighborhood`.
+    ///
+    /// This is a simultaneous search from every source. Obstructed tiles are never reached; halt
+    /// tiles are reached but not moved past, in the same way as
+    /// [`reachable_from_ctx`][Self::reachable_from_ctx]. Each step costs as much as the
+    /// neighborhood says, so with the default neighborhood, distances count steps.
+    ///
+    /// Sources are identified by their index in `sources`. A tile which is equally near to
+    /// more than one source is owned by [`Owner::Tie`]; so is any tile whose nearest sources are
+    /// only reached by way of a tie.
+    pub fn distance_field_ctx_with(
+        &self,
+        context: &<Tile as ContextInto<Traversable>>::Context,
+        neighborhood: &Neighborhood,
+        sources: impl IntoIterator<Item = Point>,
+    ) -> DistanceField {
+        let offset = self.offset();
+        let (width, height) = (self.width(), self.height());
+        let mut field = DistanceField {
+            distances: Map::procedural_offset(offset, width, height, |_| None)
+                .with_topology(self.topology()),
+            owners: Map::procedural_offset(offset, width, height, |_| None)
+                .with_topology(self.topology()),
+        };
+        let resolve = self.make_resolve();
+        let traversable = |point: Point| self[point].clone().ctx_into(point, context);
+
+        let mut settled = Map::procedural_offset(offset, width, height, |_| false);
+        let mut open_set = BinaryHeap::new();
+        for (index, source) in sources.into_iter().enumerate() {
+            let source = match resolve(source) {
+                Some(source) if traversable(source) != Traversable::Obstructed => source,
+                _ => continue,
+            };
+            let owner = Owner::Source(index);
+            field.owners[source] = Some(match field.owners[source] {
+                Some(existing) => existing.merge(owner),
+                None => owner,
+            });
+            if field.distances[source].is_none() {
+                field.distances[source] = Some(0);
+                open_set.push(Reverse((0_u32, source)));
+            }
+        }
+
+        while let Some(Reverse((distance, point))) = open_set.pop() {
+            // Every path to this point which is at least as short has already been merged into
+            // its owner, unless a free step merges in another later; then it is queued again.
+            if settled[point] {
+                continue;
+            }
+            settled[point] = true;
+            if traversable(point) == Traversable::Halt {
+                continue;
+            }
+            let owner = field.owners[point].expect("reached points have an owner");
+
+            for &(step, step_cost) in neighborhood.steps() {
+                let neighbor = match resolve(point + step) {
+                    Some(neighbor) => neighbor,
+                    None => continue,
+                };
+                if traversable(neighbor) == Traversable::Obstructed {
+                    continue;
+                }
+                let tentative_distance = distance.saturating_add(step_cost);
+                match field.distances[neighbor] {
+                    Some(known) if tentative_distance > known => {}
+                    Some(known) if tentative_distance == known => {
+                        let merged = field.owners[neighbor].map(|o| o.merge(owner));
+                        if merged != field.owners[neighbor] {
+                            field.owners[neighbor] = merged;
+                            // A settled point can only be reached this cheaply by a free step.
+                            // Its owner has become a tie, which it must pass on in turn; this
+                            // happens at most once per point.
+                            if settled[neighbor] {
+                                settled[neighbor] = false;
+                                open_set.push(Reverse((known, neighbor)));
+                            }
+                        }
+                    }
+                    _ => {
+                        field.distances[neighbor] = Some(tentative_distance);
+                        field.owners[neighbor] = Some(owner);
+                        open_set.push(Reverse((tentative_distance, neighbor)));
+                    }
+                }
+            }
+        }
+
+        field
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_voronoi_areas() {
+        // 2018 day 6 example: the largest area which does not touch the edge has 17 tiles
+        let sources =
+            [(1, 1), (1, 6), (8, 3), (3, 4), (5, 5), (8, 9)].map(|(x, y)| Point::new(x, y));
+        let map = Map::procedural(10, 10, |_| Traversable::Free);
+        let field = map.distance_field(sources);
+
+        let mut areas = HashMap::new();
+        let mut infinite = vec![false; sources.len()];
+        for (point, owner) in field.owners().iter() {
+            if let Some(Owner::Source(index)) = *owner {
+                *areas.entry(index).or_insert(0) += 1;
+                if point.x == map.low_x()
+                    || point.x == map.high_x()
+                    || point.y == map.low_y()
+                    || point.y == map.high_y()
+                {
+                    infinite[index] = true;
+                }
+            }
+        }
+        let largest = areas
+            .iter()
+            .filter(|(&index, _)| !infinite[index])
+            .map(|(_, &area)| area)
+            .max();
+        assert_eq!(largest, Some(17));
+        assert_eq!(field.owner(Point::new(0, 4)), Some(Owner::Tie));
+        assert_eq!(field.distance(Point::new(0, 0)), Some(2));
+    }
+
+    #[test]
+    fn test_obstacles_and_halts() {
+        // .#H.
+        // S...
+        let map = Map::procedural(4, 2, |point| match (point.x, point.y) {
+            (1, 1) => Traversable::Obstructed,
+            (2, 1) => Traversable::Halt,
+            _ => Traversable::Free,
+        });
+        let field = map.distance_field([Point::new(0, 0), Point::new(1, 1)]);
+        assert_eq!(field.distance(Point::new(1, 1)), None);
+        assert_eq!(field.owner(Point::new(1, 1)), None);
+        assert_eq!(field.distance(Point::new(2, 1)), Some(3));
+        // only reachable around the halt
+        assert_eq!(field.distance(Point::new(3, 1)), Some(4));
+        assert_eq!(field.owner(Point::new(3, 1)), Some(Owner::Source(0)));
+    }
+
+    #[test]
+    fn test_zero_cost_ties() {
+        // free horizontal steps make every tile of a row as near as its nearest end
+        let neighborhood = Neighborhood::custom_weighted([
+            (Point::new(1, 0), 0),
+            (Point::new(-1, 0), 0),
+            (Point::new(0, 1), 1),
+            (Point::new(0, -1), 1),
+        ]);
+        let map = Map::procedural(5, 2, |_| Traversable::Free);
+        for sources in [
+            [Point::new(0, 0), Point::new(4, 0)],
+            [Point::new(4, 0), Point::new(0, 0)],
+        ] {
+            let field = map.distance_field_with(&neighborhood, sources);
+            for point in map.points() {
+                assert_eq!(field.distance(point), Some(point.y as u32));
+                assert_eq!(field.owner(point), Some(Owner::Tie), "{:?}", point);
+            }
+        }
+    }
+}
@@ -0,0 +1,310 @@
+use super::Map;
+use crate::geometry::Point;
+use std::{
+    hash::{Hash, Hasher},
+    ops::{Deref, Index, IndexMut},
+};
+
+/// Minimum number of tiles of headroom added to an axis when a map grows.
+const MIN_HEADROOM: usize = 8;
+
+/// A dense [`Map`] which grows when written outside its bounds.
+///
+/// Reading outside the bounds produces the fill tile supplied at construction. Writing outside
+/// the bounds reallocates the map so that it includes the written point, adjusting its offset
+/// as necessary. Each reallocation adds headroom proportional to the current size of the map,
+/// so the cost of growth is amortized across many writes.
+///
+/// All of [`Map`]'s read-only methods are available through `Deref`. Note that they see the
+/// current allocation, headroom included; use [`shrink_to_fit`][Self::shrink_to_fit] or
+/// [`shrink_to`][Self::shrink_to] to trim the map back to its occupied region, for example
+/// before displaying it.
+///
+/// ## Example
+///
+/// ```
+/// # use aoclib::geometry::{map::GrowingMap, tile::Bool, Point};
+/// let mut map = GrowingMap::new(Bool::False);
+/// map[Point::new(0, 0)] = Bool::True;
+/// map[Point::new(-3, 2)] = Bool::True;
+/// assert_eq!(map[Point::new(-100, -100)], Bool::False);
+/// assert!(map.in_bounds(Point::new(-3, 2)));
+///
+/// map.shrink_to_fit();
+/// assert_eq!(map.to_string(), "#...\n....\n...#\n");
+/// ```
+#[derive(Clone, Debug)]
+pub struct GrowingMap<Tile> {
+    map: Map<Tile>,
+    fill: Tile,
+}
+
+impl<Tile: Clone + Default> Default for GrowingMap<Tile> {
+    fn default() -> Self {
+        GrowingMap::new(Tile::default())
+    }
+}
+
+impl<Tile: Clone + Default> From<Map<Tile>> for GrowingMap<Tile> {
+    /// Points outside the map hold `Tile::default()`.
+    fn from(map: Map<Tile>) -> Self {
+        GrowingMap::from_map(map, Tile::default())
+    }
+}
+
+impl<Tile> Deref for GrowingMap<Tile> {
+    type Target = Map<Tile>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.map
+    }
+}
+
+impl<Tile> GrowingMap<Tile> {
+    /// Wrap an existing map. Points outside it hold `fill`.
+    pub fn from_map(map: Map<Tile>, fill: Tile) -> GrowingMap<Tile> {
+        GrowingMap { map, fill }
+    }
+
+    /// The tile held by every point outside the current bounds.
+    pub fn fill_tile(&self) -> &Tile {
+        &self.fill
+    }
+
+    /// The underlying map.
+    pub fn as_map(&self) -> &Map<Tile> {
+        &self.map
+    }
+
+    /// The underlying map, mutably.
+    ///
+    /// Writes through this reference do not grow the map.
+    pub fn as_map_mut(&mut self) -> &mut Map<Tile> {
+        &mut self.map
+    }
+
+    /// Unwrap the underlying map.
+    pub fn into_map(self) -> Map<Tile> {
+        self.map
+    }
+
+    /// Get the tile at a point; this is the fill tile if the point is out of bounds.
+    pub fn get(&self, point: Point) -> &Tile {
+        if self.map.in_bounds(point) {
+            &self.map[point]
+        } else {
+            &self.fill
+        }
+    }
+}
+
+impl<Tile: Clone> GrowingMap<Tile> {
+    /// Create a new, empty map, in which every point holds `fill`.
+    pub fn new(fill: Tile) -> GrowingMap<Tile> {
+        GrowingMap {
+            map: Map::procedural(0, 0, |_| fill.clone()),
+            fill,
+        }
+    }
+
+    /// Get a mutable reference to the tile at a point, growing the map if necessary.
+    pub fn get_mut(&mut self, point: Point) -> &mut Tile {
+        self.reserve_to_include(point);
+        &mut self.map[point]
+    }
+
+    /// Store a tile at a point, growing the map if necessary, and return the previous tile.
+    pub fn insert(&mut self, point: Point, tile: Tile) -> Tile {
+        std::mem::replace(self.get_mut(point), tile)
+    }
+
+    /// Ensure that `point` is within the bounds of the map, reallocating if necessary.
+    ///
+    /// When reallocation is required, each axis which must grow gains headroom of half its
+    /// current length beyond the point, so that repeated growth in one direction is amortized.
+    pub fn reserve_to_include(&mut self, point: Point) {
+        if self.map.in_bounds(point) {
+            return;
+        }
+
+        // an empty map has no meaningful bounds; start from the point itself
+        let (low, high) = if self.map.width() == 0 || self.map.height() == 0 {
+            (point, point)
+        } else {
+            (self.map.bottom_left(), self.map.top_right())
+        };
+        let (low_x, high_x) = grow_axis(low.x, high.x, point.x);
+        let (low_y, high_y) = grow_axis(low.y, high.y, point.y);
+
+        let old = &self.map;
+        let fill = &self.fill;
+        self.map = Map::procedural_offset(
+            Point::new(low_x, low_y),
+            (high_x - low_x + 1) as usize,
+            (high_y - low_y + 1) as usize,
+            |point| {
+                if old.in_bounds(point) {
+                    old[point].clone()
+                } else {
+                    fill.clone()
+                }
+            },
+        );
+    }
+
+    /// Shrink the map to the smallest region containing every point which is interesting
+    /// according to the predicate.
+    ///
+    /// If no point is interesting, the map becomes empty.
+    pub fn shrink_to(&mut self, is_interesting: impl Fn(Point, &Tile) -> bool) {
+        if self
+            .map
+            .iter()
+            .any(|(point, tile)| is_interesting(point, tile))
+        {
+            self.map = self.map.extract_interesting_region(is_interesting);
+        } else {
+            let fill = &self.fill;
+            self.map = Map::procedural(0, 0, |_| fill.clone());
+        }
+    }
+}
+
+impl<Tile: Clone + PartialEq> GrowingMap<Tile> {
+    /// Shrink the map to the smallest region containing every tile which differs from the fill tile.
+    pub fn shrink_to_fit(&mut self) {
+        let fill = self.fill.clone();
+        self.shrink_to(|_, tile| *tile != fill);
+    }
+}
+
+impl<Tile: PartialEq> GrowingMap<Tile> {
+    /// Iterate over the tiles which differ from the fill tile, in scan order.
+    ///
+    /// Scan order depends only on the points, not on the current allocation.
+    fn occupied(&self) -> impl '_ + Iterator<Item = (Point, &Tile)> {
+        self.map.iter().filter(move |(_, tile)| **tile != self.fill)
+    }
+}
+
+impl<Tile: PartialEq> PartialEq for GrowingMap<Tile> {
+    /// Two growing maps are equal when they hold the same tile at every point.
+    ///
+    /// Neither the headroom nor the offset of the current allocation affects equality.
+    fn eq(&self, other: &Self) -> bool {
+        self.fill == other.fill
+            && self
+                .occupied()
+                .all(|(point, tile)| tile == other.get(point))
+            && other
+                .occupied()
+                .all(|(point, tile)| tile == self.get(point))
+    }
+}
+
+impl<Tile: Eq> Eq for GrowingMap<Tile> {}
+
+impl<Tile: PartialEq + Hash> Hash for GrowingMap<Tile> {
+    /// Hashes the fill tile and the tiles which differ from it, consistently with equality.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.fill.hash(state);
+        for (point, tile) in self.occupied() {
+            point.hash(state);
+            tile.hash(state);
+        }
+    }
+}
+
+/// Compute new inclusive bounds for one axis so that it includes `coord`, with headroom.
+fn grow_axis(low: i32, high: i32, coord: i32) -> (i32, i32) {
+    let headroom = (((high - low + 1) as usize / 2).max(MIN_HEADROOM)) as i32;
+    if coord < low {
+        (coord - headroom, high)
+    } else if coord > high {
+        (low, coord + headroom)
+    } else {
+        (low, high)
+    }
+}
+
+impl<Tile> Index<Point> for GrowingMap<Tile> {
+    type Output = Tile;
+
+    /// Produces the fill tile if the point is out of bounds.
+    fn index(&self, point: Point) -> &Tile {
+        self.get(point)
+    }
+}
+
+impl<Tile: Clone> IndexMut<Point> for GrowingMap<Tile> {
+    /// Grows the map if the point is out of bounds.
+    fn index_mut(&mut self, point: Point) -> &mut Tile {
+        self.get_mut(point)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_growth_preserves_tiles() {
+        let mut map = GrowingMap::new(0);
+        for x in -20..20 {
+            map[Point::new(x, x * 2)] = x + 100;
+        }
+        for x in -20..20 {
+            assert_eq!(map[Point::new(x, x * 2)], x + 100);
+        }
+        assert_eq!(map[Point::new(1000, 0)], 0);
+    }
+
+    #[test]
+    fn test_growth_is_amortized() {
+        let mut map = GrowingMap::new(false);
+        let mut reallocations = 0;
+        for x in 0..1000 {
+            if !map.in_bounds(Point::new(x, 0)) {
+                reallocations += 1;
+            }
+            map[Point::new(x, 0)] = true;
+        }
+        assert!(reallocations < 20, "{} reallocations", reallocations);
+    }
+
+    #[test]
+    fn test_equality_ignores_allocation() {
+        use std::collections::hash_map::DefaultHasher;
+
+        let hash = |map: &GrowingMap<u8>| {
+            let mut hasher = DefaultHasher::new();
+            map.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        let mut left = GrowingMap::new(0_u8);
+        left.insert(Point::new(3, 3), 1);
+        let mut right = GrowingMap::new(0_u8);
+        right.insert(Point::new(-50, 20), 0);
+        right.insert(Point::new(3, 3), 1);
+        assert_ne!(left.as_map(), right.as_map());
+        assert_eq!(left, right);
+        assert_eq!(hash(&left), hash(&right));
+
+        right.insert(Point::new(4, 3), 2);
+        assert_ne!(left, right);
+    }
+
+    #[test]
+    fn test_shrink() {
+        let mut map = GrowingMap::new(0_u8);
+        map.insert(Point::new(-5, -5), 1);
+        map.insert(Point::new(-3, -4), 2);
+        map.shrink_to_fit();
+        assert_eq!(map.offset(), Point::new(-5, -5));
+        assert_eq!((map.width(), map.height()), (3, 2));
+        map.shrink_to(|_, _| false);
+        assert_eq!(map.width(), 0);
+        assert_eq!(map[Point::new(-5, -5)], 0);
+    }
+}
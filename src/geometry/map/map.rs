@@ -1,8 +1,13 @@
-use super::{a_star::AStarNode, tile::DisplayWidth, ContextInto, Edge, Traversable};
-use crate::geometry::{Direction, Point};
+use super::{
+    a_star::AStarNode, tile::DisplayWidth, ContextInto, Edge, Neighborhood, Topology, Traversable,
+};
+use crate::{
+    geometry::{Direction, Point},
+    input::{read_rows, BlankRow, Normalization},
+};
 use bitvec::bitvec;
 use std::{
-    collections::{BinaryHeap, HashMap, VecDeque},
+    collections::{BinaryHeap, VecDeque},
     convert::TryFrom,
     fmt::{self, Write as _},
     hash,
@@ -10,6 +15,8 @@ use std::{
     str::FromStr,
 };
 
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 #[cfg(feature = "map-render")]
 use {
     super::{
@@ -43,6 +50,7 @@ pub struct Map<Tile> {
     width: usize,
     height: usize,
     offset: Point,
+    topology: Topology,
 }
 
 impl<Tile> Map<Tile> {
@@ -67,6 +75,7 @@ impl<Tile> Map<Tile> {
             width,
             height,
             offset,
+            topology: Topology::Bounded,
         };
         for idx in 0..area {
             let point = map.index2point(idx);
@@ -177,6 +186,106 @@ impl<Tile> Map<Tile> {
         (0..self.tiles.len()).map(index2point)
     }
 
+    /// How this map treats its edges.
+    #[inline]
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// Change how this map treats its edges.
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.topology = topology;
+    }
+
+    /// Change how this map treats its edges.
+    pub fn with_topology(mut self, topology: Topology) -> Self {
+        self.topology = topology;
+        self
+    }
+
+    /// Reduce a point modulo the dimensions of this map, producing a point in bounds.
+    ///
+    /// This is independent of the map's topology.
+    ///
+    /// ## Panics
+    ///
+    /// If the map is empty.
+    pub fn wrap(&self, point: Point) -> Point {
+        self.make_wrap()(point)
+    }
+
+    /// Make a function which reduces a point modulo the dimensions of this map, without borrowing `self`.
+    pub fn make_wrap(&self) -> impl Fn(Point) -> Point {
+        let offset = self.offset;
+        let width = self.width as i32;
+        let height = self.height as i32;
+        move |point| {
+            let relative = point - offset;
+            Point::new(relative.x.rem_euclid(width), relative.y.rem_euclid(height)) + offset
+        }
+    }
+
+    /// Make a function which maps a point which may be just off an edge of this map onto
+    /// the map according to its topology.
+    ///
+    /// On a bounded map, the function returns `None` for out-of-bounds points.
+    /// On a toroidal map, it always wraps the point.
+    pub(crate) fn make_resolve(&self) -> impl Fn(Point) -> Option<Point> {
+        let in_bounds = self.make_in_bounds();
+        let wrap = self.make_wrap();
+        let topology = self.topology;
+        move |point| match topology {
+            Topology::Bounded => in_bounds(point).then_some(point),
+            Topology::Toroidal => Some(wrap(point)),
+        }
+    }
+
+    /// Shortest absolute displacement along each axis between two points, accounting for this
+    /// map's topology.
+    fn topological_delta(&self, a: Point, b: Point) -> (u32, u32) {
+        let delta = b - a;
+        match self.topology {
+            Topology::Bounded => (delta.x.unsigned_abs(), delta.y.unsigned_abs()),
+            Topology::Toroidal => {
+                let dx = (delta.x.rem_euclid(self.width as i32)) as u32;
+                let dy = (delta.y.rem_euclid(self.height as i32)) as u32;
+                (
+                    dx.min(self.width as u32 - dx),
+                    dy.min(self.height as u32 - dy),
+                )
+            }
+        }
+    }
+
+    /// Shortest manhattan distance between two points, accounting for this map's topology.
+    fn topological_distance(&self, a: Point, b: Point) -> u32 {
+        let (dx, dy) = self.topological_delta(a, b);
+        dx + dy
+    }
+
+    /// Reconstruct the path from `from` to `to`, given the step which reached each point.
+    ///
+    /// `came_from` is indexed by `point_index`; `offset` converts a step into the offset it moves by.
+    fn backtrack<Step: Copy>(
+        &self,
+        came_from: &[Option<Step>],
+        from: Point,
+        to: Point,
+        offset: impl Fn(Step) -> Point,
+    ) -> Vec<Step> {
+        let resolve = self.make_resolve();
+        let mut current = to;
+        let mut path = Vec::new();
+        while current != from {
+            let step =
+                came_from[self.point_index(current)].expect("reached points have a predecessor");
+            path.push(step);
+            current = resolve(current - offset(step)).expect("predecessor is on the map");
+        }
+        path.reverse();
+        path
+    }
+
     /// `true` when a point is legal within the bounds of this map.
     #[inline]
     pub fn in_bounds(&self, point: Point) -> bool {
@@ -204,8 +313,26 @@ impl<Tile> Map<Tile> {
         x + (y * self.width)
     }
 
+    /// convert a point into a 1d index into the tiles
+    ///
+    /// Panics if the point is below or to the left of the map's offset, so negative
+    /// coordinates are legal when the offset permits them. Toroidal maps wrap the point first.
+    pub(crate) fn point_index(&self, point: Point) -> usize {
+        let point = match self.topology {
+            Topology::Bounded => point,
+            Topology::Toroidal => self.wrap(point),
+        };
+        assert!(
+            point.x >= self.offset.x && point.y >= self.offset.y,
+            "point must not be below or left of the map's offset"
+        );
+        let x = (point.x - self.offset.x) as usize;
+        let y = (point.y - self.offset.y) as usize;
+        x + (y * self.width)
+    }
+
     /// convert a 1d index in the tiles into a 2d point
-    fn index2point(&self, idx: usize) -> Point {
+    pub(crate) fn index2point(&self, idx: usize) -> Point {
         let unoffset: Point = (idx % self.width, idx / self.width).into();
         unoffset + self.offset
     }
@@ -224,23 +351,38 @@ impl<Tile> Map<Tile> {
     /// Return an iterator of all legal points adjacent to the given point.
     ///
     /// This iterator will return up to 8 elements; it includes diagonals.
+    /// On a toroidal map, adjacencies wrap around the edges.
     pub fn adjacencies(&self, point: Point) -> impl Iterator<Item = Point> {
-        let in_bounds = self.make_in_bounds();
+        let resolve = self.make_resolve();
         self.orthogonal_adjacencies(point).chain(
             Direction::iter_diag()
-                .map(move |(vertical, horizontal)| point + vertical + horizontal)
-                .filter(move |&point| in_bounds(point)),
+                .filter_map(move |(vertical, horizontal)| resolve(point + vertical + horizontal)),
         )
     }
 
     /// Return an iterator of all legal points orthogonally adjacent to the given point,
     ///
     /// This iterator will return up to 4 elements; it does not include diagonals.
+    /// On a toroidal map, adjacencies wrap around the edges.
     pub fn orthogonal_adjacencies(&self, point: Point) -> impl Iterator<Item = Point> {
-        let in_bounds = self.make_in_bounds();
-        Direction::iter()
-            .map(move |direction| point + direction)
-            .filter(move |&point| in_bounds(point))
+        let resolve = self.make_resolve();
+        Direction::iter().filter_map(move |direction| resolve(point + direction))
+    }
+
+    /// Return an iterator of all legal points which are neighbors of the given point
+    /// according to `neighborhood`.
+    ///
+    /// On a toroidal map, neighbors wrap around the edges.
+    pub fn neighbors<'a>(
+        &'a self,
+        point: Point,
+        neighborhood: &'a Neighborhood,
+    ) -> impl 'a + Iterator<Item = Point> {
+        let resolve = self.make_resolve();
+        neighborhood
+            .steps()
+            .iter()
+            .filter_map(move |&(step, _)| resolve(point + step))
     }
 
     /// Return an iterator of all legal points adjacent to the given point,
@@ -255,11 +397,10 @@ impl<Tile> Map<Tile> {
     where
         Tile: 'static,
     {
-        let in_bounds = self.make_in_bounds();
+        let resolve = self.make_resolve();
         self.make_orthogonal_adjacencies(point).chain(
             Direction::iter_diag()
-                .map(move |(vertical, horizontal)| point + vertical + horizontal)
-                .filter(move |&point| in_bounds(point)),
+                .filter_map(move |(vertical, horizontal)| resolve(point + vertical + horizontal)),
         )
     }
 
@@ -275,19 +416,22 @@ impl<Tile> Map<Tile> {
     where
         Tile: 'static,
     {
-        let in_bounds = self.make_in_bounds();
-        Direction::iter()
-            .map(move |direction| point + direction)
-            .filter(move |&point| in_bounds(point))
+        let resolve = self.make_resolve();
+        Direction::iter().filter_map(move |direction| resolve(point + direction))
     }
 
     /// Return an iterator of all legal points arrived at by applying the given deltas to the origin.
     ///
     /// The origin point is always the first item in this iteration.
+    ///
+    /// On a toroidal map, the projection wraps around the edges, and ends just before it would
+    /// return to the origin.
     pub fn project(&self, origin: Point, dx: i32, dy: i32) -> impl Iterator<Item = Point> {
-        let in_bounds = self.make_in_bounds();
-        std::iter::successors(Some(origin), move |&current| Some(current + (dx, dy)))
-            .take_while(move |&point| in_bounds(point))
+        let resolve = self.make_resolve();
+        let start = resolve(origin);
+        std::iter::successors(start, move |&current| {
+            resolve(current + (dx, dy)).filter(|&next| Some(next) != start)
+        })
     }
 
     /// Create an iterator over the points on the edge of this map.
@@ -356,8 +500,20 @@ impl<Tile> Map<Tile> {
             width: self.width,
             height: self.height,
             offset: self.offset,
+            topology: self.topology,
         }
     }
+
+    /// Consume this map, producing its tiles in index order.
+    pub(crate) fn into_tiles(self) -> Vec<Tile> {
+        self.tiles
+    }
+
+    /// Exchange the tiles at two points.
+    pub(crate) fn swap(&mut self, a: Point, b: Point) {
+        let (a, b) = (self.point_index(a), self.point_index(b));
+        self.tiles.swap(a, b);
+    }
 }
 
 impl<Tile: Clone> Map<Tile> {
@@ -385,6 +541,7 @@ impl<Tile: Clone> Map<Tile> {
         let offset = min;
 
         Self::procedural_offset(offset, width, height, |point| self[point].clone())
+            .with_topology(self.topology)
     }
 }
 
@@ -407,6 +564,7 @@ impl<Tile: Clone + Default> Map<Tile> {
             width,
             height,
             offset,
+            topology: Topology::Bounded,
         }
     }
 
@@ -415,6 +573,7 @@ impl<Tile: Clone + Default> Map<Tile> {
     /// This does not adjust the offset; the corners remain where they previously were.
     pub fn flip_vertical(&self) -> Map<Tile> {
         let mut flipped = Map::new_offset(self.offset, self.width, self.height);
+        flipped.topology = self.topology;
 
         for y in 0..(self.height as i32) {
             let flipped_y = self.high_y() - y;
@@ -432,6 +591,7 @@ impl<Tile: Clone + Default> Map<Tile> {
     /// This does not adjust the offset; the corners remain where they previously were.
     pub fn flip_horizontal(&self) -> Map<Tile> {
         let mut flipped = Map::new_offset(self.offset, self.width, self.height);
+        flipped.topology = self.topology;
 
         for y in self.low_y()..=self.high_y() {
             for x in 0..(self.width as i32) {
@@ -461,6 +621,7 @@ impl<Tile: Clone + Default> Map<Tile> {
         );
 
         let mut rotated = Map::new(self.height, self.width);
+        rotated.topology = self.topology;
 
         let rotated_origin = rotated.bottom_right();
         for point in self.points() {
@@ -488,6 +649,7 @@ impl<Tile: Clone + Default> Map<Tile> {
         );
 
         let mut rotated = Map::new(self.height, self.width);
+        rotated.topology = self.topology;
 
         let rotated_origin = rotated.top_left();
         for point in self.points() {
@@ -505,6 +667,7 @@ impl<Tile> fmt::Debug for Map<Tile> {
             .field("width", &self.width)
             .field("height", &self.height)
             .field("offset", &self.offset)
+            .field("topology", &self.topology)
             .field("tiles", &format_args!("[...; {}]", self.tiles.len()))
             .finish()
     }
@@ -516,6 +679,7 @@ impl<Tile: hash::Hash> hash::Hash for Map<Tile> {
         self.width.hash(state);
         self.height.hash(state);
         self.offset.hash(state);
+        self.topology.hash(state);
     }
 }
 
@@ -524,6 +688,7 @@ impl<Tile: PartialEq> PartialEq for Map<Tile> {
         self.width == other.width
             && self.height == other.height
             && self.offset == other.offset
+            && self.topology == other.topology
             && self.tiles == other.tiles
     }
 }
@@ -550,6 +715,7 @@ where
                 width: 0,
                 height: 0,
                 offset: Point::default(),
+                topology: Topology::Bounded,
             };
         }
 
@@ -574,6 +740,7 @@ where
             width,
             height,
             offset: Point::default(),
+            topology: Topology::Bounded,
         }
     }
 }
@@ -596,25 +763,36 @@ where
     ///
     /// That doesn't stop us from doing it here, and implementing the official trait for
     /// a few concrete types
+    ///
+    /// The input is normalized with the default [`Normalization`], which strips a byte-order
+    /// mark and `\r\n` line endings but preserves other whitespace.
     pub fn try_from<R>(input: R) -> Result<Self, MapConversionErr>
     where
         R: std::io::BufRead,
     {
-        let mut arr = Vec::new();
-
-        for line in input.lines() {
-            let line = line?;
+        Self::try_from_with(input, Normalization::default())
+    }
 
-            let mut row = Vec::with_capacity(line.len() / Tile::DISPLAY_WIDTH);
-            for chunk in Tile::chunks(&line) {
-                row.push(Tile::from_str(&chunk).map_err(|err| {
-                    MapConversionErr::TileConversion(Box::new(err), chunk.to_string())
-                })?);
-            }
-            if !row.is_empty() {
-                arr.push(row);
-            }
-        }
+    /// Try to convert the contents of a reader into a map, normalizing the input according
+    /// to the provided policy.
+    ///
+    /// Lines which are empty after normalization are skipped.
+    pub fn try_from_with<R>(
+        input: R,
+        normalization: Normalization,
+    ) -> Result<Self, MapConversionErr>
+    where
+        R: std::io::BufRead,
+    {
+        let mut arr = read_rows(input, normalization, |line| {
+            Tile::chunks(line)
+                .map(|chunk| {
+                    Tile::from_str(&chunk).map_err(|err| {
+                        MapConversionErr::TileConversion(Box::new(err), chunk.to_string())
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })?;
 
         if !arr.is_empty() {
             let width = arr[0].len();
@@ -669,7 +847,7 @@ where
     /// its first characters are the top left.
     fn try_from(path: &std::path::Path) -> Result<Self, Self::Error> {
         <Self as TryFrom<std::fs::File>>::try_from(std::fs::File::open(path)?)
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, Box::new(e)))
+            .map_err(|e| std::io::Error::other(Box::new(e)))
     }
 }
 
@@ -684,13 +862,11 @@ impl<Tile> Index<(usize, usize)> for Map<Tile> {
 impl<Tile> Index<Point> for Map<Tile> {
     type Output = Tile;
 
-    /// Panics if `point.x < 0 || point.y < 0`
+    /// On a bounded map, panics if `point.x < self.low_x() || point.y < self.low_y()`.
+    /// On a toroidal map, the point is first wrapped into bounds.
     fn index(&self, point: Point) -> &Tile {
-        assert!(
-            point.x >= 0 && point.y >= 0,
-            "point must be in the positive quadrant"
-        );
-        self.index((point.x as usize, point.y as usize))
+        let idx = self.point_index(point);
+        self.tiles.index(idx)
     }
 }
 
@@ -701,13 +877,11 @@ impl<Tile> IndexMut<(usize, usize)> for Map<Tile> {
 }
 
 impl<Tile> IndexMut<Point> for Map<Tile> {
-    /// Panics if `point.x < 0 || point.y < 0`
+    /// On a bounded map, panics if `point.x < self.low_x() || point.y < self.low_y()`.
+    /// On a toroidal map, the point is first wrapped into bounds.
     fn index_mut(&mut self, point: Point) -> &mut Tile {
-        assert!(
-            point.x >= 0 && point.y >= 0,
-            "point must be in the positive quadrant"
-        );
-        self.index_mut((point.x as usize, point.y as usize))
+        let idx = self.point_index(point);
+        self.tiles.index_mut(idx)
     }
 }
 
@@ -776,6 +950,77 @@ where
     }
 }
 
+#[cfg(feature = "rayon")]
+impl<Tile> Map<Tile>
+where
+    Tile: Send + Sync,
+{
+    /// Procedurally create a new `Map` from a function, evaluating it in parallel.
+    ///
+    /// _Depends on the `rayon` feature._
+    pub fn par_procedural(
+        width: usize,
+        height: usize,
+        procedure: impl Fn(Point) -> Tile + Sync,
+    ) -> Map<Tile> {
+        Self::par_procedural_offset(Point::default(), width, height, procedure)
+    }
+
+    /// Procedurally create a new `Map` from a function with an offset origin, evaluating it in parallel.
+    ///
+    /// _Depends on the `rayon` feature._
+    pub fn par_procedural_offset(
+        offset: Point,
+        width: usize,
+        height: usize,
+        procedure: impl Fn(Point) -> Tile + Sync,
+    ) -> Map<Tile> {
+        let mut map = Map {
+            tiles: Vec::new(),
+            width,
+            height,
+            offset,
+            topology: Topology::Bounded,
+        };
+        let index2point = map.make_index2point();
+        map.tiles = (0..width * height)
+            .into_par_iter()
+            .map(|idx| procedure(index2point(idx)))
+            .collect();
+        map
+    }
+
+    /// Iterate in parallel over the points and tiles of this map.
+    ///
+    /// _Depends on the `rayon` feature._
+    pub fn par_iter(&self) -> impl '_ + IndexedParallelIterator<Item = (Point, &Tile)> {
+        let index2point = self.make_index2point();
+        self.tiles
+            .par_iter()
+            .enumerate()
+            .map(move |(idx, tile)| (index2point(idx), tile))
+    }
+
+    /// Iterate in parallel over the points of this map, with mutable access to the tiles.
+    ///
+    /// _Depends on the `rayon` feature._
+    pub fn par_iter_mut(&mut self) -> impl '_ + IndexedParallelIterator<Item = (Point, &mut Tile)> {
+        let index2point = self.make_index2point();
+        self.tiles
+            .par_iter_mut()
+            .enumerate()
+            .map(move |(idx, tile)| (index2point(idx), tile))
+    }
+
+    /// Iterate in parallel over the points of this map without depending on the lifetime of `self`.
+    ///
+    /// _Depends on the `rayon` feature._
+    pub fn par_points(&self) -> impl IndexedParallelIterator<Item = Point> {
+        let index2point = self.make_index2point();
+        (0..self.tiles.len()).into_par_iter().map(index2point)
+    }
+}
+
 #[cfg(feature = "map-render")]
 impl<Tile> Map<Tile>
 where
@@ -863,11 +1108,38 @@ where
         self.reachable_from_ctx(&(), point, visit)
     }
 
+    /// Visit every non-obstructed tile reachable from the initial point, moving between
+    /// neighbors as defined by `neighborhood`.
+    ///
+    /// If the visitor ever returns true, processing halts and no further
+    /// points are visited.
+    pub fn reachable_from_with(
+        &self,
+        neighborhood: &Neighborhood,
+        point: Point,
+        visit: impl FnMut(Point, &Tile) -> bool,
+    ) {
+        self.reachable_from_ctx_with(&(), neighborhood, point, visit)
+    }
+
     /// navigate between the given points using A*
     // https://en.wikipedia.org/wiki/A*_search_algorithm#Pseudocode
     pub fn navigate(&self, from: Point, to: Point) -> Option<Vec<Direction>> {
         self.navigate_ctx(&(), from, to)
     }
+
+    /// navigate between the given points using A*, moving between neighbors as defined by
+    /// `neighborhood`
+    ///
+    /// See [`navigate_ctx_with`][Self::navigate_ctx_with].
+    pub fn navigate_with(
+        &self,
+        neighborhood: &Neighborhood,
+        from: Point,
+        to: Point,
+    ) -> Option<(u32, Vec<Point>)> {
+        self.navigate_ctx_with(&(), neighborhood, from, to)
+    }
 }
 
 impl<Tile: Clone + ContextInto<Traversable>> Map<Tile> {
@@ -879,13 +1151,28 @@ impl<Tile: Clone + ContextInto<Traversable>> Map<Tile> {
         &self,
         context: &<Tile as ContextInto<Traversable>>::Context,
         point: Point,
+        visit: impl FnMut(Point, &Tile) -> bool,
+    ) {
+        self.reachable_from_ctx_with(context, &Neighborhood::orthogonal(), point, visit)
+    }
+
+    /// Visit every non-obstructed tile reachable from the initial point, moving between
+    /// neighbors as defined by `neighborhood`.
+    ///
+    /// If the visitor ever returns true, processing halts and no further
+    /// points are visited.
+    pub fn reachable_from_ctx_with(
+        &self,
+        context: &<Tile as ContextInto<Traversable>>::Context,
+        neighborhood: &Neighborhood,
+        point: Point,
         mut visit: impl FnMut(Point, &Tile) -> bool,
     ) {
         let mut visited = bitvec!(0; self.tiles.len());
         let mut queue = VecDeque::new();
         queue.push_back(point);
 
-        let idx = |point: Point| self.point2index(point.x as usize, point.y as usize);
+        let idx = |point: Point| self.point_index(point);
 
         while let Some(point) = queue.pop_front() {
             // we may have scheduled a single point more than once via alternate paths;
@@ -906,7 +1193,7 @@ impl<Tile: Clone + ContextInto<Traversable>> Map<Tile> {
             }
 
             if traversable == Traversable::Free {
-                for neighbor in self.orthogonal_adjacencies(point) {
+                for neighbor in self.neighbors(point, neighborhood) {
                     if !visited[idx(neighbor)] {
                         queue.push_back(neighbor);
                     }
@@ -916,6 +1203,8 @@ impl<Tile: Clone + ContextInto<Traversable>> Map<Tile> {
     }
 
     /// navigate between the given points using A*
+    ///
+    /// Obstructed tiles cannot be entered; every other step costs 1.
     // https://en.wikipedia.org/wiki/A*_search_algorithm#Pseudocode
     pub fn navigate_ctx(
         &self,
@@ -923,75 +1212,168 @@ impl<Tile: Clone + ContextInto<Traversable>> Map<Tile> {
         from: Point,
         to: Point,
     ) -> Option<Vec<Direction>> {
+        let (cost, steps) =
+            self.navigate_ctx_with(context, &Neighborhood::orthogonal(), from, to)?;
+        debug_assert!(cost >= self.topological_distance(from, to));
+        Some(
+            steps
+                .into_iter()
+                .map(|step| Direction::try_from(step).expect("orthogonal steps are directions"))
+                .collect(),
+        )
+    }
+
+    /// navigate between the given points using A*, moving between neighbors as defined by
+    /// `neighborhood`
+    ///
+    /// Obstructed tiles cannot be entered; each step costs as much as the neighborhood says.
+    ///
+    /// Returns the total cost of the cheapest path, and each step along it as an offset from
+    /// the neighborhood.
+    // https://en.wikipedia.org/wiki/A*_search_algorithm#Pseudocode
+    pub fn navigate_ctx_with(
+        &self,
+        context: &<Tile as ContextInto<Traversable>>::Context,
+        neighborhood: &Neighborhood,
+        from: Point,
+        to: Point,
+    ) -> Option<(u32, Vec<Point>)> {
+        let resolve = self.make_resolve();
+        let from = resolve(from)?;
+        let to = resolve(to)?;
+        let heuristic = |point: Point| {
+            let (dx, dy) = self.topological_delta(point, to);
+            neighborhood.min_cost(dx, dy)
+        };
+
+        // gscore, indexed by `point_index`: cost of cheapest known path from start to node
+        let mut cheapest_path_cost = vec![u32::MAX; self.tiles.len()];
+        // the step which reached each node on the cheapest known path from start
+        let mut came_from: Vec<Option<Point>> = vec![None; self.tiles.len()];
+
+        // Nodes are never removed from or updated within the open set. Instead, a cheaper path
+        // to a node pushes it again, and stale entries are skipped when popped.
         let mut open_set = BinaryHeap::new();
+        cheapest_path_cost[self.point_index(from)] = 0;
         open_set.push(AStarNode {
+            estimate: heuristic(from),
             cost: 0,
             position: from,
         });
 
-        // key: node
-        // value: node preceding it on the cheapest known path from start
-        let mut came_from = HashMap::new();
+        while let Some(AStarNode { cost, position, .. }) = open_set.pop() {
+            if position == to {
+                return Some((cost, self.backtrack(&came_from, from, to, |step| step)));
+            }
 
-        // gscore
-        // key: position
-        // value: cost of cheapest path from start to node
-        let mut cheapest_path_cost = HashMap::new();
-        cheapest_path_cost.insert(from, 0_u32);
+            if cost > cheapest_path_cost[self.point_index(position)] {
+                continue;
+            }
 
-        // fscore
-        // key: position
-        // value: best guess as to total cost from here to finish
-        let mut total_cost_guess = HashMap::new();
-        total_cost_guess.insert(from, (to - from).manhattan() as u32);
+            for &(step, step_cost) in neighborhood.steps() {
+                let neighbor = match resolve(position + step) {
+                    Some(neighbor) => neighbor,
+                    None => continue,
+                };
+                if self[neighbor].clone().ctx_into(neighbor, context) == Traversable::Obstructed {
+                    continue;
+                }
 
-        while let Some(AStarNode { cost, position }) = open_set.pop() {
-            if position == to {
-                let mut current = position;
-                let mut path = Vec::new();
-                while let Some((direction, predecessor)) = came_from.remove(&current) {
-                    current = predecessor;
-                    path.push(direction);
+                let tentative_cost = cost.saturating_add(step_cost);
+                let index = self.point_index(neighbor);
+                if tentative_cost < cheapest_path_cost[index] {
+                    // this path to the neighbor is better than any previous one
+                    cheapest_path_cost[index] = tentative_cost;
+                    came_from[index] = Some(step);
+                    open_set.push(AStarNode {
+                        estimate: tentative_cost.saturating_add(heuristic(neighbor)),
+                        cost: tentative_cost,
+                        position: neighbor,
+                    });
                 }
-                debug_assert!(path.len() as i32 >= (to - from).manhattan());
-                path.reverse();
-                return Some(path);
+            }
+        }
+
+        None
+    }
+}
+
+impl<Tile> Map<Tile>
+where
+    Tile: Clone + ContextInto<Option<u32>, Context = ()>,
+{
+    /// Navigate between the given points using Dijkstra's algorithm, where each tile has a cost.
+    ///
+    /// See [`navigate_weighted_ctx`][Self::navigate_weighted_ctx].
+    pub fn navigate_weighted(&self, from: Point, to: Point) -> Option<(u32, Vec<Direction>)> {
+        self.navigate_weighted_ctx(&(), from, to)
+    }
+}
+
+impl<Tile: Clone + ContextInto<Option<u32>>> Map<Tile> {
+    /// Navigate between the given points using Dijkstra's algorithm, where each tile has a cost.
+    ///
+    /// Each tile converts into the cost of stepping onto it; `None` means the tile is impassable.
+    /// The cost of the starting tile is never paid.
+    ///
+    /// Returns the total cost of the cheapest path, and the steps along it.
+    // https://en.wikipedia.org/wiki/Dijkstra%27s_algorithm#Using_a_priority_queue
+    pub fn navigate_weighted_ctx(
+        &self,
+        context: &<Tile as ContextInto<Option<u32>>>::Context,
+        from: Point,
+        to: Point,
+    ) -> Option<(u32, Vec<Direction>)> {
+        let resolve = self.make_resolve();
+        let from = resolve(from)?;
+        let to = resolve(to)?;
+
+        // indexed by `point_index`
+        let mut cheapest_path_cost = vec![u32::MAX; self.tiles.len()];
+        let mut came_from: Vec<Option<Direction>> = vec![None; self.tiles.len()];
+
+        let mut open_set = BinaryHeap::new();
+        cheapest_path_cost[self.point_index(from)] = 0;
+        open_set.push(AStarNode {
+            estimate: 0,
+            cost: 0,
+            position: from,
+        });
+
+        while let Some(AStarNode { cost, position, .. }) = open_set.pop() {
+            if position == to {
+                return Some((
+                    cost,
+                    self.backtrack(&came_from, from, to, |direction| {
+                        Point::default() + direction
+                    }),
+                ));
+            }
+
+            // a cheaper path to this point has already been processed
+            if cost > cheapest_path_cost[self.point_index(position)] {
+                continue;
             }
 
             for direction in Direction::iter() {
-                let neighbor = position + direction;
-                if !self.in_bounds(neighbor) {
-                    continue;
-                }
-                match self[neighbor].clone().ctx_into(neighbor, context) {
-                    Traversable::Obstructed => {}
-                    Traversable::Free | Traversable::Halt => {
-                        let tentative_cheapest_path_cost = cost + 1;
-                        if tentative_cheapest_path_cost
-                            < cheapest_path_cost
-                                .get(&neighbor)
-                                .cloned()
-                                .unwrap_or(u32::MAX)
-                        {
-                            // this path to the neighbor is better than any previous one
-                            came_from.insert(neighbor, (direction, position));
-                            cheapest_path_cost.insert(neighbor, tentative_cheapest_path_cost);
-                            total_cost_guess.insert(
-                                neighbor,
-                                tentative_cheapest_path_cost + (to - neighbor).manhattan() as u32,
-                            );
-
-                            // this thing with the iterator is not very efficient, but for some weird reason BinaryHeap
-                            // doesn't have a .contains method; see
-                            // https://github.com/rust-lang/rust/issues/66724
-                            if !open_set.iter().any(|elem| elem.position == neighbor) {
-                                open_set.push(AStarNode {
-                                    cost: tentative_cheapest_path_cost,
-                                    position: neighbor,
-                                });
-                            }
-                        }
-                    }
+                let neighbor = match resolve(position + direction) {
+                    Some(neighbor) => neighbor,
+                    None => continue,
+                };
+                let step_cost = match self[neighbor].clone().ctx_into(neighbor, context) {
+                    Some(step_cost) => step_cost,
+                    None => continue,
+                };
+                let tentative_cost = cost.saturating_add(step_cost);
+                let index = self.point_index(neighbor);
+                if tentative_cost < cheapest_path_cost[index] {
+                    cheapest_path_cost[index] = tentative_cost;
+                    came_from[index] = Some(direction);
+                    open_set.push(AStarNode {
+                        estimate: tentative_cost,
+                        cost: tentative_cost,
+                        position: neighbor,
+                    });
                 }
             }
         }
@@ -1010,6 +1392,8 @@ pub enum MapConversionErr {
     #[error("map must be rectangular")]
     NotRectangular,
     #[error(transparent)]
+    BlankRow(#[from] BlankRow),
+    #[error(transparent)]
     Io(#[from] std::io::Error),
 }
 
@@ -1121,6 +1505,24 @@ mod tests {
         assert_eq!(map.height, 2);
         assert_eq!(map.offset, Point::new(1, 0));
         assert_eq!(map.tiles, vec![1, 2]);
+
+        let toroidal = Map::procedural(2, 2, |point| point.x).with_topology(Topology::Toroidal);
+        let region = toroidal.extract_interesting_region(|_, &tile| tile == 1);
+        assert_eq!(region.topology(), Topology::Toroidal);
+    }
+
+    #[test]
+    fn test_blank_rows() {
+        use crate::geometry::map::tile::Bool;
+
+        let map = <Map<Bool> as TryFrom<&str>>::try_from("\n#.\n.#\n\n").unwrap();
+        assert_eq!(map.height(), 2);
+
+        let result = <Map<Bool> as TryFrom<&str>>::try_from("#.\n\n.#\n");
+        assert!(matches!(
+            result,
+            Err(MapConversionErr::BlankRow(BlankRow { line: 2 }))
+        ));
     }
 
     #[test]
@@ -1159,6 +1561,189 @@ mod tests {
         assert_eq!(flip_map.flip_horizontal(), map);
     }
 
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_parallel_matches_sequential() {
+        let offset = Point::new(-3, 2);
+        let mut map = Map::par_procedural_offset(offset, 7, 5, |point| point.x * point.y);
+        assert_eq!(
+            map,
+            Map::procedural_offset(offset, 7, 5, |point| point.x * point.y)
+        );
+        assert!(map.par_points().collect::<Vec<_>>() == map.points().collect::<Vec<_>>());
+        map.par_iter_mut()
+            .for_each(|(point, tile)| *tile -= point.x);
+        assert!(map
+            .par_iter()
+            .all(|(point, &tile)| tile == point.x * point.y - point.x));
+    }
+
+    #[test]
+    fn test_toroidal_adjacency_and_indexing() {
+        let map = Map::procedural_offset(Point::new(-1, -1), 3, 3, |point| point)
+            .with_topology(Topology::Toroidal);
+        assert_eq!(map[Point::new(2, -4)], Point::new(-1, -1));
+        assert_eq!(map.orthogonal_adjacencies(Point::new(1, 1)).count(), 4);
+        assert_eq!(map.adjacencies(Point::new(1, 1)).count(), 8);
+        assert!(map
+            .adjacencies(Point::new(1, 1))
+            .all(|point| map.in_bounds(point)));
+        assert!(map
+            .orthogonal_adjacencies(Point::new(1, 1))
+            .any(|point| point == Point::new(-1, 1)));
+    }
+
+    #[test]
+    fn test_toroidal_project_stops_at_origin() {
+        let map = Map::<u8>::new(4, 3).with_topology(Topology::Toroidal);
+        let projected: Vec<_> = map.project(Point::new(3, 0), 1, 1).collect();
+        // lcm(4, 3) == 12 steps before the projection returns to its origin
+        assert_eq!(projected.len(), 12);
+        assert_eq!(projected[1], Point::new(0, 1));
+    }
+
+    #[test]
+    fn test_toroidal_navigation() {
+        let map: Map<Traversable> = (&[
+            [
+                Traversable::Free,
+                Traversable::Obstructed,
+                Traversable::Free,
+            ],
+            [
+                Traversable::Free,
+                Traversable::Obstructed,
+                Traversable::Free,
+            ],
+        ][..])
+            .into();
+        assert_eq!(map.navigate(Point::new(0, 0), Point::new(2, 0)), None);
+        let map = map.with_topology(Topology::Toroidal);
+        assert_eq!(
+            map.navigate(Point::new(0, 0), Point::new(2, 0)),
+            Some(vec![Direction::Left])
+        );
+        let mut reachable = 0;
+        map.reachable_from(Point::new(0, 0), |_, _| {
+            reachable += 1;
+            false
+        });
+        assert_eq!(reachable, 4);
+    }
+
+    #[test]
+    fn test_navigate_finds_shortest_path() {
+        // walls with a single gap each, forcing long detours
+        let map = Map::procedural(40, 30, |point| {
+            if point.y % 4 == 2 && point.x != (point.y * 11) % 40 {
+                Traversable::Obstructed
+            } else {
+                Traversable::Free
+            }
+        });
+        let unit_costs = Map::procedural(map.width(), map.height(), |point| {
+            (map[point] == Traversable::Free).then_some(1_u32)
+        });
+
+        for (from, to) in [
+            (map.bottom_left(), map.top_right()),
+            (map.top_left(), map.bottom_right()),
+            (Point::new(0, 0), Point::new(0, 29)),
+        ] {
+            let path = map.navigate(from, to).unwrap();
+            let (cost, _) = unit_costs.navigate_weighted(from, to).unwrap();
+            assert_eq!(path.len() as u32, cost);
+
+            let mut position = from;
+            for direction in path {
+                position += direction;
+                assert_eq!(map[position], Traversable::Free);
+            }
+            assert_eq!(position, to);
+        }
+    }
+
+    #[test]
+    fn test_navigate_with_neighborhoods() {
+        let map = Map::procedural(30, 20, |point| {
+            if point.y % 4 == 2 && point.x != (point.y * 7) % 30 {
+                Traversable::Obstructed
+            } else {
+                Traversable::Free
+            }
+        });
+        let (from, to) = (map.bottom_left(), map.top_right());
+
+        for diagonal_cost in [1, 2, 3] {
+            let neighborhood = Neighborhood::moore().with_diagonal_cost(diagonal_cost);
+            let (cost, steps) = map.navigate_with(&neighborhood, from, to).unwrap();
+            let expect = crate::search::dijkstra(
+                from,
+                |&point| {
+                    neighborhood
+                        .steps()
+                        .iter()
+                        .filter(|&&(step, _)| {
+                            map.in_bounds(point + step) && map[point + step] == Traversable::Free
+                        })
+                        .map(|&(step, cost)| (point + step, cost))
+                        .collect::<Vec<_>>()
+                },
+                |&point| point == to,
+            )
+            .unwrap();
+            assert_eq!(cost, expect.cost);
+            assert_eq!(steps.iter().fold(from, |point, &step| point + step), to);
+        }
+
+        // knights jump the walls
+        let mut reachable = 0;
+        map.reachable_from_with(&Neighborhood::knight(), from, |_, _| {
+            reachable += 1;
+            false
+        });
+        assert_eq!(
+            reachable,
+            map.iter()
+                .filter(|(_, &tile)| tile == Traversable::Free)
+                .count()
+        );
+    }
+
+    #[test]
+    fn test_navigate_weighted_chiton() {
+        let map = <Map<Digit> as TryFrom<&str>>::try_from(
+            "1163751742\n1381373672\n2136511328\n3694931569\n7463417111\n\
+             1319128137\n1359912421\n3125421639\n1293138521\n2311944581\n",
+        )
+        .unwrap();
+        let (cost, path) = map
+            .navigate_weighted(map.top_left(), map.bottom_right())
+            .unwrap();
+        assert_eq!(cost, 40);
+
+        let mut position = map.top_left();
+        let mut walked = 0;
+        for direction in path {
+            position += direction;
+            walked += u32::from(u8::from(map[position]));
+        }
+        assert_eq!(position, map.bottom_right());
+        assert_eq!(walked, cost);
+    }
+
+    #[test]
+    fn test_navigate_weighted_impassable() {
+        let map = Map::procedural(3, 3, |point| (point.x != 1).then_some(1_u32));
+        assert!(map
+            .navigate_weighted(Point::new(0, 0), Point::new(2, 0))
+            .is_none());
+        assert_eq!(
+            map.navigate_weighted(Point::new(0, 0), Point::new(0, 2)),
+            Some((2, vec![Direction::Up, Direction::Up]))
+        );
+    }
+
     #[test]
     fn test_rotate_left() {
         let map = Map::<Digit>::procedural(3, 2, |point| {
@@ -1,20 +1,40 @@
 mod a_star;
 mod context_conversions;
+mod distance_field;
 mod edge;
+mod growing;
 // This interior module is private; we reexport its contents.
 #[allow(clippy::module_inception)]
 mod map;
+mod neighborhood;
+mod pattern;
+mod regions;
 #[cfg(feature = "map-render")]
 mod render;
+mod shortest_paths;
+mod sparse;
+mod steering;
+mod symmetry;
+mod topology;
 mod traversable;
 
 pub mod tile;
 
 pub use context_conversions::{ContextFrom, ContextInto};
+pub use distance_field::{DistanceField, Owner};
 pub use edge::Edge;
+pub use growing::GrowingMap;
 #[cfg(feature = "map-render")]
 pub use map::RenderError;
 pub use map::{Map, MapConversionErr};
+pub use neighborhood::Neighborhood;
+pub use pattern::{Cell, Match, Pattern, Search};
+pub use regions::{Region, RegionId, Regions};
 #[cfg(feature = "map-render")]
 pub use render::{Animation, EncodingError, Style};
+pub use shortest_paths::{Paths, ShortestPaths};
+pub use sparse::SparseMap;
+pub use steering::{Maneuver, Steering};
+pub use symmetry::Symmetry;
+pub use topology::Topology;
 pub use traversable::Traversable;
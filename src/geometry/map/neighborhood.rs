@@ -0,0 +1,176 @@
+use crate::geometry::Point;
+
+/// The lowest cost per unit of distance achievable by any step, as a fraction `cost / length`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Rate {
+    cost: u64,
+    length: u64,
+}
+
+impl Rate {
+    /// Find the cheapest rate among the steps, measuring step length with the given metric.
+    fn cheapest(steps: &[(Point, u32)], metric: impl Fn(Point) -> u64) -> Option<Rate> {
+        steps
+            .iter()
+            .map(|&(offset, cost)| Rate {
+                cost: cost.into(),
+                length: metric(offset),
+            })
+            .min_by(|a, b| (a.cost * b.length).cmp(&(b.cost * a.length)))
+    }
+
+    /// Lower bound on the cost of traversing `distance` at this rate.
+    fn apply(self, distance: u64) -> u64 {
+        (distance * self.cost).div_ceil(self.length)
+    }
+}
+
+fn manhattan(offset: Point) -> u64 {
+    offset.manhattan() as u64
+}
+
+fn chebyshev(offset: Point) -> u64 {
+    offset.x.abs().max(offset.y.abs()) as u64
+}
+
+/// The steps which lead from a point to its neighbors, and the cost of each.
+///
+/// Flood fills and pathfinding on a [`Map`][super::Map] can accept a neighborhood to control
+/// which points count as adjacent. The default is [`orthogonal`][Self::orthogonal].
+///
+/// ## Example
+///
+/// ```
+/// # use aoclib::geometry::{map::{Neighborhood, Traversable}, Map, Point};
+/// let map = Map::procedural(8, 8, |_| Traversable::Free);
+/// let (cost, steps) = map
+///     .navigate_with(&Neighborhood::knight(), Point::new(0, 0), Point::new(7, 7))
+///     .unwrap();
+/// assert_eq!(cost, 6);
+/// assert_eq!(steps.len(), 6);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Neighborhood {
+    steps: Vec<(Point, u32)>,
+    // Precomputed so that the A* heuristic is cheap.
+    manhattan_rate: Option<Rate>,
+    chebyshev_rate: Option<Rate>,
+}
+
+impl Default for Neighborhood {
+    fn default() -> Self {
+        Neighborhood::orthogonal()
+    }
+}
+
+impl Neighborhood {
+    /// The four orthogonal neighbors (the von Neumann neighborhood), each costing 1.
+    pub fn orthogonal() -> Neighborhood {
+        Neighborhood::custom([(0, 1), (0, -1), (-1, 0), (1, 0)].map(|(x, y)| Point::new(x, y)))
+    }
+
+    /// All eight neighbors including diagonals (the Moore neighborhood), each costing 1.
+    ///
+    /// Use [`with_diagonal_cost`][Self::with_diagonal_cost] to make diagonal steps more expensive.
+    pub fn moore() -> Neighborhood {
+        Neighborhood::custom(
+            [
+                (0, 1),
+                (0, -1),
+                (-1, 0),
+                (1, 0),
+                (-1, 1),
+                (1, 1),
+                (-1, -1),
+                (1, -1),
+            ]
+            .map(|(x, y)| Point::new(x, y)),
+        )
+    }
+
+    /// The eight moves of a chess knight, each costing 1.
+    pub fn knight() -> Neighborhood {
+        Neighborhood::custom(
+            [
+                (1, 2),
+                (2, 1),
+                (2, -1),
+                (1, -2),
+                (-1, -2),
+                (-2, -1),
+                (-2, 1),
+                (-1, 2),
+            ]
+            .map(|(x, y)| Point::new(x, y)),
+        )
+    }
+
+    /// Arbitrary offsets, each costing 1.
+    ///
+    /// A zero offset is ignored.
+    pub fn custom(offsets: impl IntoIterator<Item = Point>) -> Neighborhood {
+        Neighborhood::custom_weighted(offsets.into_iter().map(|offset| (offset, 1)))
+    }
+
+    /// Arbitrary offsets, each with its own cost.
+    ///
+    /// A zero offset is ignored.
+    pub fn custom_weighted(steps: impl IntoIterator<Item = (Point, u32)>) -> Neighborhood {
+        let steps: Vec<_> = steps
+            .into_iter()
+            .filter(|&(offset, _)| offset != Point::default())
+            .collect();
+        Neighborhood {
+            manhattan_rate: Rate::cheapest(&steps, manhattan),
+            chebyshev_rate: Rate::cheapest(&steps, chebyshev),
+            steps,
+        }
+    }
+
+    /// Set the cost of every diagonal step: that is, every step which moves along both axes.
+    pub fn with_diagonal_cost(self, cost: u32) -> Neighborhood {
+        Neighborhood::custom_weighted(self.steps.into_iter().map(|(offset, old_cost)| {
+            if offset.x != 0 && offset.y != 0 {
+                (offset, cost)
+            } else {
+                (offset, old_cost)
+            }
+        }))
+    }
+
+    /// Each offset in this neighborhood, and the cost of stepping along it.
+    pub fn steps(&self) -> &[(Point, u32)] {
+        &self.steps
+    }
+
+    /// Lower bound on the cost of moving by `(dx, dy)` using only steps from this neighborhood.
+    ///
+    /// This is an admissible heuristic for A*.
+    pub(crate) fn min_cost(&self, dx: u32, dy: u32) -> u32 {
+        let (dx, dy) = (u64::from(dx), u64::from(dy));
+        let by_manhattan = self.manhattan_rate.map_or(0, |rate| rate.apply(dx + dy));
+        let by_chebyshev = self.chebyshev_rate.map_or(0, |rate| rate.apply(dx.max(dy)));
+        by_manhattan.max(by_chebyshev).min(u32::MAX.into()) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_min_cost() {
+        assert_eq!(Neighborhood::orthogonal().min_cost(3, 4), 7);
+        assert_eq!(Neighborhood::moore().min_cost(3, 4), 4);
+        assert_eq!(
+            Neighborhood::moore().with_diagonal_cost(2).min_cost(3, 4),
+            7
+        );
+        assert_eq!(
+            Neighborhood::moore().with_diagonal_cost(3).min_cost(3, 4),
+            7
+        );
+        assert_eq!(Neighborhood::knight().min_cost(7, 7), 5);
+        assert_eq!(Neighborhood::custom(None).min_cost(7, 7), 0);
+    }
+}
@@ -0,0 +1,457 @@
+use super::{Map, Symmetry};
+use crate::{
+    geometry::Point,
+    input::{read_rows, Normalization},
+};
+use std::{collections::HashSet, fmt, rc::Rc};
+
+/// One cell of a [`Pattern`].
+pub enum Cell<Tile> {
+    /// Matches any tile.
+    Any,
+    /// Matches tiles equal to this one.
+    Is(Tile),
+    /// Matches tiles for which the predicate returns `true`.
+    Where(Rc<dyn Fn(&Tile) -> bool>),
+}
+
+impl<Tile> Cell<Tile> {
+    /// A cell which matches tiles satisfying a predicate.
+    pub fn predicate(predicate: impl 'static + Fn(&Tile) -> bool) -> Cell<Tile> {
+        Cell::Where(Rc::new(predicate))
+    }
+
+    /// `true` if this cell is [`Cell::Any`].
+    pub fn is_wildcard(&self) -> bool {
+        matches!(self, Cell::Any)
+    }
+}
+
+impl<Tile: PartialEq> Cell<Tile> {
+    /// `true` if this cell matches the tile.
+    pub fn matches(&self, tile: &Tile) -> bool {
+        match self {
+            Cell::Any => true,
+            Cell::Is(expect) => expect == tile,
+            Cell::Where(predicate) => predicate(tile),
+        }
+    }
+}
+
+impl<Tile: Clone> Clone for Cell<Tile> {
+    fn clone(&self) -> Self {
+        match self {
+            Cell::Any => Cell::Any,
+            Cell::Is(tile) => Cell::Is(tile.clone()),
+            Cell::Where(predicate) => Cell::Where(predicate.clone()),
+        }
+    }
+}
+
+impl<Tile: PartialEq> PartialEq for Cell<Tile> {
+    /// Predicates are equal only when they are the same shared closure.
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Cell::Any, Cell::Any) => true,
+            (Cell::Is(a), Cell::Is(b)) => a == b,
+            (Cell::Where(a), Cell::Where(b)) => Rc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl<Tile: fmt::Debug> fmt::Debug for Cell<Tile> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Cell::Any => write!(f, "Any"),
+            Cell::Is(tile) => f.debug_tuple("Is").field(tile).finish(),
+            Cell::Where(_) => write!(f, "Where(..)"),
+        }
+    }
+}
+
+/// A rectangular arrangement of cells to search for within a [`Map`].
+///
+/// ## Example
+///
+/// ```
+/// # use aoclib::geometry::{map::{Cell, Pattern, Search}, Map, Point};
+/// let map = Map::procedural(6, 4, |point| (point.x + point.y) % 3 == 0);
+/// // a diagonal pair of `true` tiles running up and to the left
+/// let pattern = Pattern::parse("#.\n.#\n", |c| match c {
+///     '#' => Cell::Is(true),
+///     _ => Cell::Any,
+/// });
+/// assert_eq!(map.find_pattern(&pattern).len(), 5);
+///
+/// // flipped, the pattern also runs up and to the right, which never happens here
+/// let search = Search {
+///     orientations: true,
+///     ..Search::default()
+/// };
+/// assert_eq!(map.search_pattern(&pattern, &search).len(), 5);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Pattern<Tile> {
+    cells: Map<Cell<Tile>>,
+}
+
+impl<Tile> Pattern<Tile> {
+    /// Create a pattern from a map of cells.
+    ///
+    /// The pattern's origin is the lower left corner of the map, whatever its offset.
+    pub fn new(cells: Map<Cell<Tile>>) -> Pattern<Tile> {
+        Pattern { cells }
+    }
+
+    /// Create a pattern from text, in the same layout as a map: the first line is the top row.
+    ///
+    /// The text is split into rows in the same way as by `Map::try_from`: it is normalized with
+    /// the default [`Normalization`], and blank lines before and after the pattern are skipped.
+    /// Each character is converted into a cell. Short lines are padded with wildcards.
+    ///
+    /// ## Panics
+    ///
+    /// If a blank line separates two rows.
+    pub fn parse(text: &str, cell: impl Fn(char) -> Cell<Tile>) -> Pattern<Tile> {
+        let lines = read_rows(text.as_bytes(), Normalization::default(), |line| {
+            Ok::<_, std::io::Error>(line.chars().collect::<Vec<_>>())
+        })
+        .unwrap_or_else(|err| panic!("{}", err));
+        let width = lines.iter().map(Vec::len).max().unwrap_or_default();
+        let height = lines.len();
+        Pattern::new(Map::procedural(width, height, |point| {
+            lines[height - 1 - point.y as usize]
+                .get(point.x as usize)
+                .map_or(Cell::Any, |&c| cell(c))
+        }))
+    }
+
+    /// Width of this pattern.
+    pub fn width(&self) -> usize {
+        self.cells.width()
+    }
+
+    /// Height of this pattern.
+    pub fn height(&self) -> usize {
+        self.cells.height()
+    }
+
+    /// The cells of this pattern.
+    pub fn cells(&self) -> &Map<Cell<Tile>> {
+        &self.cells
+    }
+
+    /// Each non-wildcard cell, relative to the pattern's origin, as transformed by `symmetry`.
+    fn constraints(&self, symmetry: Symmetry) -> Vec<(Point, &Cell<Tile>)> {
+        let offset = self.cells.offset();
+        let mut constraints: Vec<_> = self
+            .cells
+            .iter()
+            .filter(|(_, cell)| !cell.is_wildcard())
+            .map(|(point, cell)| {
+                let point = symmetry.transform_point(point - offset, self.width(), self.height());
+                (point, cell)
+            })
+            .collect();
+        constraints.sort_unstable_by_key(|&(point, _)| point);
+        constraints
+    }
+
+    /// Width and height of this pattern after transformation by `symmetry`.
+    fn dimensions(&self, symmetry: Symmetry) -> (usize, usize) {
+        if symmetry.swaps_axes() {
+            (self.height(), self.width())
+        } else {
+            (self.width(), self.height())
+        }
+    }
+
+    /// The points of a map covered by the non-wildcard cells of a match.
+    pub fn points(&self, found: Match) -> impl '_ + Iterator<Item = Point> {
+        self.constraints(found.symmetry)
+            .into_iter()
+            .map(move |(point, _)| point + found.origin)
+    }
+}
+
+impl<Tile: Clone> Pattern<Tile> {
+    /// Create a pattern which matches exactly the tiles of a map.
+    pub fn exact(map: &Map<Tile>) -> Pattern<Tile> {
+        Pattern::new(Map::procedural(map.width(), map.height(), |point| {
+            Cell::Is(map[point + map.offset()].clone())
+        }))
+    }
+}
+
+/// A place where a [`Pattern`] was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Match {
+    /// The point of the map at the lower left corner of the transformed pattern.
+    pub origin: Point,
+    /// How the pattern was transformed to produce this match.
+    pub symmetry: Symmetry,
+}
+
+/// How to search for a [`Pattern`], for [`Map::search_pattern`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Search {
+    /// Whether to try every orientation of the pattern, or only the original.
+    ///
+    /// When several orientations match at the same origin and cover the same tiles, only the
+    /// first is reported, so each placement of a symmetric pattern is found only once.
+    pub orientations: bool,
+    /// Whether matches may share tiles.
+    ///
+    /// When `false`, matches are taken greedily in scan order, and a match is discarded if any of
+    /// its non-wildcard cells covers a tile already covered by an earlier match.
+    pub overlapping: bool,
+}
+
+impl Default for Search {
+    /// Only the original orientation; matches may overlap.
+    fn default() -> Self {
+        Search {
+            orientations: false,
+            overlapping: true,
+        }
+    }
+}
+
+/// A pattern prepared for matching in one orientation.
+struct Orientation<'a, Tile> {
+    symmetry: Symmetry,
+    dimensions: (usize, usize),
+    constraints: Vec<(Point, &'a Cell<Tile>)>,
+}
+
+impl<Tile: PartialEq> Map<Tile> {
+    /// Find every placement of a pattern within this map, in its original orientation.
+    ///
+    /// Returns the origins of all matches, which may overlap.
+    /// See [`search_pattern`][Self::search_pattern].
+    pub fn find_pattern(&self, pattern: &Pattern<Tile>) -> Vec<Point> {
+        self.search_pattern(pattern, &Search::default())
+            .into_iter()
+            .map(|found| found.origin)
+            .collect()
+    }
+
+    /// Find placements of a pattern within this map.
+    ///
+    /// A pattern matches when every one of its cells matches the tile beneath it. It must lie
+    /// entirely within the map, whatever the map's topology.
+    ///
+    /// Matches are produced in scan order of their origins, from the bottom row up and left to
+    /// right, and then in order of their symmetry.
+    pub fn search_pattern(&self, pattern: &Pattern<Tile>, search: &Search) -> Vec<Match> {
+        let symmetries: Vec<Symmetry> = if search.orientations {
+            Symmetry::iter().collect()
+        } else {
+            vec![Symmetry::Identity]
+        };
+        let orientations: Vec<Orientation<'_, Tile>> = symmetries
+            .into_iter()
+            .map(|symmetry| Orientation {
+                symmetry,
+                dimensions: pattern.dimensions(symmetry),
+                constraints: pattern.constraints(symmetry),
+            })
+            .collect();
+
+        let mut matches = Vec::new();
+        let mut covered = HashSet::new();
+        for origin in self.points() {
+            // orientations which cover the same tiles from this origin, and match, are the same
+            // placement; comparing cells can't show this, as distinct predicates never compare equal
+            let mut placed: Vec<&[(Point, &Cell<Tile>)]> = Vec::new();
+            for Orientation {
+                symmetry,
+                dimensions: (width, height),
+                constraints,
+            } in &orientations
+            {
+                let fits = origin.x + *width as i32 - 1 <= self.high_x()
+                    && origin.y + *height as i32 - 1 <= self.high_y();
+                if !fits
+                    || !constraints
+                        .iter()
+                        .all(|&(point, cell)| cell.matches(&self[origin + point]))
+                {
+                    continue;
+                }
+                let same_tiles = |other: &&[(Point, &Cell<Tile>)]| {
+                    other.len() == constraints.len()
+                        && other
+                            .iter()
+                            .zip(constraints)
+                            .all(|(&(a, _), &(b, _))| a == b)
+                };
+                if placed.iter().any(same_tiles) {
+                    continue;
+                }
+                placed.push(constraints);
+                if !search.overlapping {
+                    if constraints
+                        .iter()
+                        .any(|&(point, _)| covered.contains(&(origin + point)))
+                    {
+                        continue;
+                    }
+                    covered.extend(constraints.iter().map(|&(point, _)| origin + point));
+                }
+                matches.push(Match {
+                    origin,
+                    symmetry: *symmetry,
+                });
+            }
+        }
+        matches
+    }
+}
+
+impl<Tile> Map<Tile> {
+    /// Apply `mask` to every tile covered by a non-wildcard cell of any of the matches.
+    ///
+    /// Each tile is masked once, even if several matches cover it.
+    pub fn mask_matches(
+        &mut self,
+        pattern: &Pattern<Tile>,
+        matches: &[Match],
+        mut mask: impl FnMut(&mut Tile),
+    ) {
+        let points: HashSet<Point> = matches
+            .iter()
+            .flat_map(|&found| pattern.points(found))
+            .collect();
+        for point in points {
+            mask(&mut self[point]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, parse_display::FromStr, parse_display::Display)]
+    #[from_str(regex = "(?P<0>.)")]
+    struct Letter(char);
+
+    impl crate::geometry::tile::DisplayWidth for Letter {
+        const DISPLAY_WIDTH: usize = 1;
+    }
+
+    fn letters(text: &str) -> Pattern<Letter> {
+        Pattern::parse(text, |c| match c {
+            '.' => Cell::Any,
+            c => Cell::Is(Letter(c)),
+        })
+    }
+
+    #[test]
+    fn test_word_search() {
+        // 2024 day 4 example
+        let map = <Map<Letter> as TryFrom<&str>>::try_from(
+            "MMMSXXMASM\nMSAMXMSMSA\nAMXSXMAAMM\nMSAMASMSMX\nXMASAMXAMM\n\
+             XXAMMXXAMA\nSMSMSASXSS\nSAXAMASAAA\nMAMMMXMMMM\nMXMXAXMASX\n",
+        )
+        .unwrap();
+        let search = Search {
+            orientations: true,
+            ..Search::default()
+        };
+
+        let straight = map.search_pattern(&letters("XMAS"), &search).len();
+        let diagonal = map
+            .search_pattern(&letters("...S\n..A.\n.M..\nX...\n"), &search)
+            .len();
+        assert_eq!(straight + diagonal, 18);
+
+        let cross = letters("M.S\n.A.\nM.S\n");
+        assert_eq!(map.search_pattern(&cross, &search).len(), 9);
+    }
+
+    #[test]
+    fn test_overlapping() {
+        let map = Map::procedural(5, 1, |_| Letter('a'));
+        let pattern = letters("aa");
+        assert_eq!(map.find_pattern(&pattern).len(), 4);
+
+        let disjoint = Search {
+            overlapping: false,
+            ..Search::default()
+        };
+        let matches = map.search_pattern(&pattern, &disjoint);
+        let origins: Vec<_> = matches.iter().map(|found| found.origin.x).collect();
+        assert_eq!(origins, vec![0, 2]);
+    }
+
+    #[test]
+    fn test_parse_like_map() {
+        let pattern = letters("\n\r\nab\r\nc\n\n");
+        assert_eq!((pattern.width(), pattern.height()), (2, 2));
+        assert_eq!(pattern.cells()[Point::new(0, 1)], Cell::Is(Letter('a')));
+        assert_eq!(pattern.cells()[Point::new(1, 0)], Cell::Any);
+    }
+
+    #[test]
+    #[should_panic(expected = "line 2: blank row within grid")]
+    fn test_parse_rejects_interior_blank_row() {
+        letters("ab\n\ncd\n");
+    }
+
+    #[test]
+    fn test_symmetric_predicates() {
+        let map = Map::procedural(4, 1, |_| true);
+        let search = Search {
+            orientations: true,
+            ..Search::default()
+        };
+        let exact = Pattern::parse("##", |_| Cell::Is(true));
+        let predicate = Pattern::parse("##", |_| Cell::predicate(|&tile: &bool| tile));
+        assert_eq!(map.search_pattern(&exact, &search).len(), 3);
+        assert_eq!(map.search_pattern(&predicate, &search).len(), 3);
+    }
+
+    #[test]
+    fn test_mask_sea_monsters() {
+        let monster = Pattern::parse(
+            "                  # \n#    ##    ##    ###\n #  #  #  #  #  #   \n",
+            |c| match c {
+                '#' => Cell::predicate(|&tile: &bool| tile),
+                _ => Cell::Any,
+            },
+        );
+        assert_eq!((monster.width(), monster.height()), (20, 3));
+
+        // two monsters, one of them upside down, among some noise
+        let mut map = Map::procedural(24, 8, |point| (point.x * 7 + point.y * 3) % 11 == 0);
+        let upright = Match {
+            origin: Point::new(1, 0),
+            symmetry: Symmetry::Identity,
+        };
+        let flipped = Match {
+            origin: Point::new(3, 5),
+            symmetry: Symmetry::FlipVertical,
+        };
+        for &found in &[upright, flipped] {
+            for point in monster.points(found) {
+                map[point] = true;
+            }
+        }
+        let before = map.iter().filter(|(_, &tile)| tile).count();
+
+        let search = Search {
+            orientations: true,
+            ..Search::default()
+        };
+        let matches = map.search_pattern(&monster, &search);
+        assert_eq!(matches, vec![upright, flipped]);
+
+        map.mask_matches(&monster, &matches, |tile| *tile = false);
+        let after = map.iter().filter(|(_, &tile)| tile).count();
+        assert_eq!(before - after, 2 * 15);
+    }
+}
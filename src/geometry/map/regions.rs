@@ -0,0 +1,311 @@
+use super::{Map, Topology};
+use crate::geometry::{Direction, Point};
+use std::ops::Index;
+
+/// Identifies a region within [`Regions`].
+///
+/// Regions are numbered consecutively from 0, in the order in which they were found.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, parse_display::Display,
+)]
+pub struct RegionId(usize);
+
+impl RegionId {
+    /// This region's position among the regions.
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// Summary of a single connected region of a map.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Region {
+    id: RegionId,
+    point: Point,
+    area: usize,
+    perimeter: usize,
+    sides: usize,
+    bottom_left: Point,
+    top_right: Point,
+    touches_edge: bool,
+}
+
+impl Region {
+    /// This region's id.
+    pub fn id(&self) -> RegionId {
+        self.id
+    }
+
+    /// An arbitrary point within this region.
+    pub fn point(&self) -> Point {
+        self.point
+    }
+
+    /// Number of tiles in this region.
+    pub fn area(&self) -> usize {
+        self.area
+    }
+
+    /// Number of tile edges which separate this region from anything else, including the
+    /// outside of the map.
+    pub fn perimeter(&self) -> usize {
+        self.perimeter
+    }
+
+    /// Number of straight sides on this region's boundary, counting holes.
+    ///
+    /// A straight side may span many tile edges; it ends at every corner.
+    pub fn sides(&self) -> usize {
+        self.sides
+    }
+
+    /// Lower left and upper right corners of the smallest rectangle containing this region.
+    ///
+    /// This is only meaningful for [`Topology::Bounded`] maps. On a toroidal map, a region which
+    /// wraps around the seam spans from one edge to the other, however small it is.
+    pub fn bounds(&self) -> (Point, Point) {
+        (self.bottom_left, self.top_right)
+    }
+
+    /// `true` if any tile of this region lies on the edge of the map.
+    ///
+    /// A toroidal map has no edges, so this is always `false` unless the map is
+    /// [`Topology::Bounded`].
+    pub fn touches_edge(&self) -> bool {
+        self.touches_edge
+    }
+}
+
+/// The connected regions of a map.
+///
+/// Created by [`Map::regions`] and [`Map::regions_where`].
+///
+/// ## Example
+///
+/// ```
+/// # use aoclib::geometry::{Map, Point};
+/// # use std::convert::TryFrom;
+/// # #[derive(Clone, Copy, PartialEq, Eq, parse_display::FromStr, parse_display::Display)]
+/// # #[from_str(regex = "(?P<0>.)")]
+/// # struct Plant(char);
+/// # impl aoclib::geometry::tile::DisplayWidth for Plant {
+/// #     const DISPLAY_WIDTH: usize = 1;
+/// # }
+/// let map = <Map<Plant> as TryFrom<&str>>::try_from("AAAA\nBBCD\nBBCC\nEEEC\n").unwrap();
+/// let regions = map.regions();
+/// assert_eq!(regions.len(), 5);
+/// let price: usize = regions.iter().map(|region| region.area() * region.perimeter()).sum();
+/// assert_eq!(price, 140);
+/// let discount: usize = regions.iter().map(|region| region.area() * region.sides()).sum();
+/// assert_eq!(discount, 80);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Regions {
+    labels: Map<Option<RegionId>>,
+    regions: Vec<Region>,
+}
+
+impl Regions {
+    /// Label the connected components of a map.
+    ///
+    /// Only points for which `eligible` is true are labelled. Orthogonally adjacent eligible
+    /// points belong to the same region when `connected` is true for them.
+    fn label<Tile>(
+        map: &Map<Tile>,
+        eligible: impl Fn(Point, &Tile) -> bool,
+        connected: impl Fn(&Tile, &Tile) -> bool,
+    ) -> Regions {
+        let mut labels = Map::procedural_offset(map.offset(), map.width(), map.height(), |_| None)
+            .with_topology(map.topology());
+        let mut regions = Vec::new();
+        let resolve = map.make_resolve();
+
+        for start in map.points() {
+            if labels[start].is_some() || !eligible(start, &map[start]) {
+                continue;
+            }
+
+            let id = RegionId(regions.len());
+            let mut members = Vec::new();
+            let mut stack = vec![start];
+            labels[start] = Some(id);
+            while let Some(point) = stack.pop() {
+                members.push(point);
+                for neighbor in map.orthogonal_adjacencies(point) {
+                    if labels[neighbor].is_none()
+                        && eligible(neighbor, &map[neighbor])
+                        && connected(&map[point], &map[neighbor])
+                    {
+                        labels[neighbor] = Some(id);
+                        stack.push(neighbor);
+                    }
+                }
+            }
+
+            let contains =
+                |point: Point| resolve(point).is_some_and(|point| labels[point] == Some(id));
+            let mut region = Region {
+                id,
+                point: start,
+                area: members.len(),
+                perimeter: 0,
+                sides: 0,
+                bottom_left: start,
+                top_right: start,
+                touches_edge: false,
+            };
+            for &point in &members {
+                region.perimeter += Direction::iter()
+                    .filter(|&direction| !contains(point + direction))
+                    .count();
+                // each corner of the boundary begins exactly one side
+                region.sides += Direction::iter_diag()
+                    .filter(|&(vertical, horizontal)| {
+                        let v = contains(point + vertical);
+                        let h = contains(point + horizontal);
+                        let outer = !v && !h;
+                        let inner = v && h && !contains(point + vertical + horizontal);
+                        outer || inner
+                    })
+                    .count();
+                region.bottom_left = Point::new(
+                    region.bottom_left.x.min(point.x),
+                    region.bottom_left.y.min(point.y),
+                );
+                region.top_right = Point::new(
+                    region.top_right.x.max(point.x),
+                    region.top_right.y.max(point.y),
+                );
+                region.touches_edge |= map.topology() == Topology::Bounded
+                    && (point.x == map.low_x()
+                        || point.x == map.high_x()
+                        || point.y == map.low_y()
+                        || point.y == map.high_y());
+            }
+            regions.push(region);
+        }
+
+        Regions { labels, regions }
+    }
+
+    /// The region containing each point, if any.
+    pub fn labels(&self) -> &Map<Option<RegionId>> {
+        &self.labels
+    }
+
+    /// The region containing a point, if any.
+    pub fn region_of(&self, point: Point) -> Option<&Region> {
+        let point = self.labels.make_resolve()(point)?;
+        self.labels[point].map(|id| &self[id])
+    }
+
+    /// Number of regions.
+    pub fn len(&self) -> usize {
+        self.regions.len()
+    }
+
+    /// `true` when there are no regions.
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+
+    /// Iterate over the regions, in order of their ids.
+    pub fn iter(&self) -> std::slice::Iter<'_, Region> {
+        self.regions.iter()
+    }
+}
+
+impl Index<RegionId> for Regions {
+    type Output = Region;
+
+    fn index(&self, id: RegionId) -> &Region {
+        &self.regions[id.0]
+    }
+}
+
+impl<'a> IntoIterator for &'a Regions {
+    type Item = &'a Region;
+    type IntoIter = std::slice::Iter<'a, Region>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<Tile: PartialEq> Map<Tile> {
+    /// Label the connected regions of equal tiles.
+    ///
+    /// Every point belongs to exactly one region.
+    pub fn regions(&self) -> Regions {
+        Regions::label(self, |_, _| true, |a, b| a == b)
+    }
+}
+
+impl<Tile> Map<Tile> {
+    /// Label the connected regions of tiles which match a predicate.
+    ///
+    /// All orthogonally adjacent matching tiles belong to the same region, whether or not the
+    /// tiles are equal. Tiles which do not match belong to no region.
+    pub fn regions_where(&self, predicate: impl Fn(Point, &Tile) -> bool) -> Regions {
+        Regions::label(self, predicate, |_, _| true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_garden_with_holes() {
+        // an O region surrounding four single-tile X regions
+        let map = Map::procedural(5, 5, |point| point.x % 2 == 1 && point.y % 2 == 1);
+        let regions = map.regions();
+        assert_eq!(regions.len(), 5);
+
+        let outer = regions.region_of(Point::new(0, 0)).unwrap();
+        assert_eq!(outer.area(), 21);
+        assert_eq!(outer.perimeter(), 36);
+        assert_eq!(outer.sides(), 20);
+        assert!(outer.touches_edge());
+        assert_eq!(outer.bounds(), (Point::new(0, 0), Point::new(4, 4)));
+
+        let inner = regions.region_of(Point::new(3, 1)).unwrap();
+        assert_eq!((inner.area(), inner.perimeter(), inner.sides()), (1, 4, 4));
+        assert!(!inner.touches_edge());
+    }
+
+    #[test]
+    fn test_wraps_seam() {
+        // one plus-shaped region of `true` tiles centered on the corner, and the rest
+        let map = Map::procedural(5, 5, |point| {
+            (point.x == 0 && point.y != 2 && point.y != 3)
+                || (point.y == 0 && point.x != 2 && point.x != 3)
+        });
+        let bounded = map.regions();
+        assert_eq!(bounded.len(), 4);
+
+        let map = map.with_topology(Topology::Toroidal);
+        let regions = map.regions();
+        assert_eq!(regions.len(), 2);
+        assert_eq!(
+            regions.region_of(Point::new(4, 0)),
+            regions.region_of(Point::new(0, 4))
+        );
+        assert_eq!(
+            regions.region_of(Point::new(-1, -1)),
+            regions.region_of(Point::new(1, 1))
+        );
+
+        let plus = regions.region_of(Point::new(0, 0)).unwrap();
+        assert_eq!(plus.area(), 5);
+        assert_eq!(plus.perimeter(), 12);
+        assert_eq!(plus.sides(), 12);
+        assert!(!plus.touches_edge());
+
+        let rest = regions.region_of(Point::new(2, 2)).unwrap();
+        assert_eq!(rest.area(), 20);
+        assert_eq!(rest.perimeter(), 12);
+        assert_eq!(rest.sides(), 12);
+        assert!(!rest.touches_edge());
+    }
+}
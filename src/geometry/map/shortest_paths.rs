@@ -0,0 +1,444 @@
+use super::{ContextInto, Map, Traversable};
+use crate::geometry::{Direction, Point};
+use num::BigUint;
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashSet},
+};
+
+/// Bit representing a direction within a predecessor set.
+fn bit(direction: Direction) -> u8 {
+    match direction {
+        Direction::Up => 1,
+        Direction::Down => 2,
+        Direction::Left => 4,
+        Direction::Right => 8,
+    }
+}
+
+/// Iterate over the directions within a predecessor set.
+fn directions(set: u8) -> impl Iterator<Item = Direction> {
+    Direction::iter().filter(move |&direction| set & bit(direction) != 0)
+}
+
+/// Every shortest path from a single origin to every reachable point of a [`Map`].
+///
+/// This is the predecessor DAG produced by a shortest-path search: each reachable point records
+/// every neighbor from which it can be reached at minimal cost. It can answer questions about all
+/// optimal paths at once, rather than just one arbitrary path.
+///
+/// Among paths of equal cost, only those with the fewest steps are cheapest. Where tiles cost
+/// nothing to enter, this keeps the number of cheapest paths finite.
+///
+/// Created by [`Map::shortest_paths`] and [`Map::shortest_paths_weighted`], and their `_ctx`
+/// variants.
+///
+/// ## Example
+///
+/// ```
+/// # use aoclib::geometry::{map::Traversable, Map, Point};
+/// let map = Map::procedural(3, 3, |_| Traversable::Free);
+/// let paths = map.shortest_paths(Point::new(0, 0));
+/// let corner = Point::new(2, 2);
+/// assert_eq!(paths.distance(corner), Some(4));
+/// assert_eq!(paths.count_paths(corner), 6_u32.into());
+/// assert_eq!(paths.paths(corner).count(), 6);
+/// assert_eq!(paths.tiles_on_paths(Point::new(2, 0)).len(), 3);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ShortestPaths {
+    from: Point,
+    /// cost of the cheapest path to each point, if it is reachable
+    distances: Map<Option<u32>>,
+    /// set of steps by which each point is reached on some cheapest path
+    predecessors: Map<u8>,
+    /// reachable points, in the order in which their distances were settled;
+    /// this is a topological order of the DAG.
+    order: Vec<Point>,
+}
+
+impl ShortestPaths {
+    /// Run Dijkstra's algorithm over the whole map, recording every optimal predecessor.
+    ///
+    /// Paths are ordered by cost, then by number of steps. Every step strictly increases that
+    /// pair, so ties always come from unsettled points and the predecessors form a DAG.
+    ///
+    /// `step_cost` produces the cost of entering a tile, or `None` if it is impassable.
+    fn compute<Tile>(
+        map: &Map<Tile>,
+        from: Point,
+        mut step_cost: impl FnMut(Point, &Tile) -> Option<u32>,
+    ) -> ShortestPaths {
+        let offset = map.offset();
+        let (width, height) = (map.width(), map.height());
+        let mut paths = ShortestPaths {
+            from,
+            distances: Map::procedural_offset(offset, width, height, |_| None)
+                .with_topology(map.topology()),
+            predecessors: Map::procedural_offset(offset, width, height, |_| 0)
+                .with_topology(map.topology()),
+            order: Vec::new(),
+        };
+        let resolve = map.make_resolve();
+        let from = match resolve(from) {
+            Some(from) => from,
+            None => return paths,
+        };
+        paths.from = from;
+
+        let mut settled = Map::procedural_offset(offset, width, height, |_| false);
+        let mut steps = Map::procedural_offset(offset, width, height, |_| 0_u32);
+        let mut open_set = BinaryHeap::new();
+        paths.distances[from] = Some(0);
+        open_set.push(Reverse((0_u32, 0_u32, from)));
+
+        while let Some(Reverse((cost, step, position))) = open_set.pop() {
+            if settled[position] {
+                continue;
+            }
+            settled[position] = true;
+            paths.order.push(position);
+
+            for direction in Direction::iter() {
+                let neighbor = match resolve(position + direction) {
+                    Some(neighbor) => neighbor,
+                    None => continue,
+                };
+                // Settled points already have all their predecessors: no step can reach them
+                // as cheaply as they were reached before.
+                if settled[neighbor] {
+                    continue;
+                }
+                let tentative_cost = match step_cost(neighbor, &map[neighbor]) {
+                    Some(step_cost) => cost.saturating_add(step_cost),
+                    None => continue,
+                };
+                let tentative = (tentative_cost, step + 1);
+                match paths.distances[neighbor].map(|known| (known, steps[neighbor])) {
+                    Some(known) if tentative > known => {}
+                    Some(known) if tentative == known => {
+                        paths.predecessors[neighbor] |= bit(direction);
+                    }
+                    _ => {
+                        paths.distances[neighbor] = Some(tentative_cost);
+                        steps[neighbor] = step + 1;
+                        paths.predecessors[neighbor] = bit(direction);
+                        open_set.push(Reverse((tentative_cost, step + 1, neighbor)));
+                    }
+                }
+            }
+        }
+
+        paths
+    }
+
+    /// Resolve a point onto the map, if it is there.
+    fn resolve(&self, point: Point) -> Option<Point> {
+        self.distances.make_resolve()(point)
+    }
+
+    /// The origin of every path.
+    pub fn from(&self) -> Point {
+        self.from
+    }
+
+    /// The cost of the cheapest path to a point, or `None` if it is unreachable.
+    pub fn distance(&self, to: Point) -> Option<u32> {
+        self.resolve(to).and_then(|to| self.distances[to])
+    }
+
+    /// The cost of the cheapest path to every point of the map.
+    pub fn distances(&self) -> &Map<Option<u32>> {
+        &self.distances
+    }
+
+    /// The immediate predecessors of a point along cheapest paths.
+    ///
+    /// Each item is the predecessor, and the step which leads from it to `point`.
+    /// This is empty for the origin and for unreachable points.
+    pub fn predecessors(&self, point: Point) -> impl '_ + Iterator<Item = (Point, Direction)> {
+        let set = self
+            .resolve(point)
+            .map_or(0, |point| self.predecessors[point]);
+        directions(set).map(move |direction| {
+            let predecessor = self
+                .resolve(point + direction.reverse())
+                .expect("predecessors are on the map");
+            (predecessor, direction)
+        })
+    }
+
+    /// Count the distinct cheapest paths to a point.
+    ///
+    /// The count can grow exponentially with distance, so it is not bounded by any fixed-width
+    /// integer.
+    /// It is zero if the point is unreachable.
+    pub fn count_paths(&self, to: Point) -> BigUint {
+        let to = match self.resolve(to) {
+            Some(to) if self.distances[to].is_some() => to,
+            _ => return BigUint::default(),
+        };
+
+        let mut counts = Map::procedural_offset(
+            self.distances.offset(),
+            self.distances.width(),
+            self.distances.height(),
+            |_| BigUint::default(),
+        )
+        .with_topology(self.distances.topology());
+        counts[self.from] = 1_u32.into();
+        for &point in self.order.iter().skip(1) {
+            let count = self
+                .predecessors(point)
+                .map(|(predecessor, _)| &counts[predecessor])
+                .sum();
+            counts[point] = count;
+            if point == to {
+                break;
+            }
+        }
+        std::mem::take(&mut counts[to])
+    }
+
+    /// Lazily enumerate every cheapest path to a point.
+    ///
+    /// Each path is the sequence of steps from the origin. The iterator is empty if the point is
+    /// unreachable.
+    pub fn paths(&self, to: Point) -> Paths<'_> {
+        let stack = match self.resolve(to) {
+            Some(to) if self.distances[to].is_some() => vec![(to, self.predecessors[to])],
+            _ => Vec::new(),
+        };
+        Paths {
+            paths: self,
+            stack,
+            steps: Vec::new(),
+        }
+    }
+
+    /// Collect every point which lies on any cheapest path to a point, including both endpoints.
+    ///
+    /// This is empty if the point is unreachable.
+    pub fn tiles_on_paths(&self, to: Point) -> HashSet<Point> {
+        let mut tiles = HashSet::new();
+        let mut queue: Vec<Point> = self
+            .resolve(to)
+            .filter(|&to| self.distances[to].is_some())
+            .into_iter()
+            .collect();
+        while let Some(point) = queue.pop() {
+            if tiles.insert(point) {
+                queue.extend(self.predecessors(point).map(|(predecessor, _)| predecessor));
+            }
+        }
+        tiles
+    }
+}
+
+/// Iterator over every cheapest path to a point.
+///
+/// Created by [`ShortestPaths::paths`].
+#[derive(Debug, Clone)]
+pub struct Paths<'a> {
+    paths: &'a ShortestPaths,
+    /// points on the partial path, walking backwards from the destination, each with the
+    /// predecessors not yet explored
+    stack: Vec<(Point, u8)>,
+    /// steps leading to each point on the stack but the first, in reverse order
+    steps: Vec<Direction>,
+}
+
+impl<'a> Iterator for Paths<'a> {
+    type Item = Vec<Direction>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (point, unexplored) = self.stack.last_mut()?;
+            let point = *point;
+
+            if point == self.paths.from {
+                let path = self.steps.iter().rev().copied().collect();
+                self.stack.pop();
+                self.steps.pop();
+                return Some(path);
+            }
+
+            match directions(*unexplored).next() {
+                None => {
+                    self.stack.pop();
+                    self.steps.pop();
+                }
+                Some(direction) => {
+                    *unexplored &= !bit(direction);
+                    let predecessor = self
+                        .paths
+                        .resolve(point + direction.reverse())
+                        .expect("predecessors are on the map");
+                    self.steps.push(direction);
+                    self.stack
+                        .push((predecessor, self.paths.predecessors[predecessor]));
+                }
+            }
+        }
+    }
+}
+
+impl<Tile> Map<Tile>
+where
+    Tile: Clone + ContextInto<Traversable, Context = ()>,
+{
+    /// Find every shortest path from a point, where every non-obstructed step costs 1.
+    ///
+    /// See [`ShortestPaths`].
+    pub fn shortest_paths(&self, from: Point) -> ShortestPaths {
+        self.shortest_paths_ctx(&(), from)
+    }
+}
+
+impl<Tile: Clone + ContextInto<Traversable>> Map<Tile> {
+    /// Find every shortest path from a point, where every non-obstructed step costs 1.
+    ///
+    /// See [`ShortestPaths`].
+    pub fn shortest_paths_ctx(
+        &self,
+        context: &<Tile as ContextInto<Traversable>>::Context,
+        from: Point,
+    ) -> ShortestPaths {
+        ShortestPaths::compute(self, from, |point, tile| {
+            (tile.clone().ctx_into(point, context) != Traversable::Obstructed).then_some(1)
+        })
+    }
+}
+
+impl<Tile> Map<Tile>
+where
+    Tile: Clone + ContextInto<Option<u32>, Context = ()>,
+{
+    /// Find every cheapest path from a point, where each tile has a cost.
+    ///
+    /// See [`navigate_weighted_ctx`][Self::navigate_weighted_ctx] for the cost model, and
+    /// [`ShortestPaths`].
+    pub fn shortest_paths_weighted(&self, from: Point) -> ShortestPaths {
+        self.shortest_paths_weighted_ctx(&(), from)
+    }
+}
+
+impl<Tile: Clone + ContextInto<Option<u32>>> Map<Tile> {
+    /// Find every cheapest path from a point, where each tile has a cost.
+    ///
+    /// See [`navigate_weighted_ctx`][Self::navigate_weighted_ctx] for the cost model, and
+    /// [`ShortestPaths`].
+    pub fn shortest_paths_weighted_ctx(
+        &self,
+        context: &<Tile as ContextInto<Option<u32>>>::Context,
+        from: Point,
+    ) -> ShortestPaths {
+        ShortestPaths::compute(self, from, |point, tile| {
+            tile.clone().ctx_into(point, context)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::map::Topology;
+
+    /// Binomial coefficient `n choose k`.
+    fn choose(n: u32, k: u32) -> BigUint {
+        (0..k).fold(BigUint::from(1_u32), |acc, i| acc * (n - i) / (i + 1))
+    }
+
+    #[test]
+    fn test_open_grid_counts_are_binomial() {
+        let map = Map::procedural(40, 40, |_| Traversable::Free);
+        let paths = map.shortest_paths(Point::new(0, 0));
+        // far too many to fit in a u64
+        assert_eq!(paths.count_paths(Point::new(39, 39)), choose(78, 39));
+        assert_eq!(paths.count_paths(Point::new(3, 2)), choose(5, 2));
+
+        let enumerated: HashSet<Vec<Direction>> = paths.paths(Point::new(3, 2)).collect();
+        assert_eq!(enumerated.len(), 10);
+        for path in enumerated {
+            assert_eq!(
+                path.iter()
+                    .fold(Point::new(0, 0), |point, &step| point + step),
+                Point::new(3, 2)
+            );
+        }
+    }
+
+    #[test]
+    fn test_tiles_on_paths_around_obstacle() {
+        // a single obstacle in the middle of a 3x3 map forces paths around either side
+        let map = Map::procedural(3, 3, |point| {
+            if point == Point::new(1, 1) {
+                Traversable::Obstructed
+            } else {
+                Traversable::Free
+            }
+        });
+        let paths = map.shortest_paths(Point::new(0, 1));
+        let to = Point::new(2, 1);
+        assert_eq!(paths.distance(to), Some(4));
+        assert_eq!(paths.count_paths(to), 2_u32.into());
+        assert_eq!(paths.tiles_on_paths(to).len(), 8);
+        assert_eq!(paths.distance(Point::new(1, 1)), None);
+        assert_eq!(paths.count_paths(Point::new(1, 1)), BigUint::default());
+        assert_eq!(paths.paths(Point::new(1, 1)).count(), 0);
+        assert_eq!(
+            paths.paths(Point::new(0, 1)).collect::<Vec<_>>(),
+            vec![vec![]]
+        );
+    }
+
+    #[test]
+    fn test_weighted_with_zero_costs() {
+        // zero-cost tiles produce ties without producing cycles
+        let map = Map::procedural(4, 1, |point| Some((point.x % 2) as u32));
+        let paths = map.shortest_paths_weighted(Point::new(0, 0));
+        assert_eq!(paths.distance(Point::new(3, 0)), Some(2));
+        assert_eq!(paths.count_paths(Point::new(3, 0)), 1_u32.into());
+
+        let toroidal = map.with_topology(Topology::Toroidal);
+        let paths = toroidal.shortest_paths_weighted(Point::new(0, 0));
+        assert_eq!(paths.distance(Point::new(3, 0)), Some(1));
+        assert_eq!(
+            paths.paths(Point::new(3, 0)).collect::<Vec<_>>(),
+            vec![vec![Direction::Left]]
+        );
+    }
+
+    #[test]
+    fn test_zero_cost_ties() {
+        // every free path costs nothing, so the fewest steps decide which are cheapest
+        let map = Map::procedural(2, 2, |_| Some(0));
+        let paths = map.shortest_paths_weighted(Point::new(0, 0));
+        let corner = Point::new(1, 1);
+        assert_eq!(paths.distance(corner), Some(0));
+        assert_eq!(paths.count_paths(corner), 2_u32.into());
+        assert_eq!(paths.paths(corner).count(), 2);
+        assert_eq!(paths.tiles_on_paths(corner).len(), 4);
+
+        let map = Map::procedural(4, 4, |_| Some(0));
+        let paths = map.shortest_paths_weighted(Point::new(0, 0));
+        assert_eq!(paths.count_paths(Point::new(3, 3)), choose(6, 3));
+
+        // a longer free detour beats a single expensive step
+        let map = Map::procedural(3, 2, |point| {
+            Some(if point == Point::new(1, 0) { 5 } else { 0 })
+        });
+        let paths = map.shortest_paths_weighted(Point::new(0, 0));
+        let to = Point::new(2, 0);
+        assert_eq!(paths.distance(to), Some(0));
+        assert_eq!(
+            paths.paths(to).collect::<Vec<_>>(),
+            vec![vec![
+                Direction::Up,
+                Direction::Right,
+                Direction::Right,
+                Direction::Down
+            ]]
+        );
+    }
+}
@@ -0,0 +1,567 @@
+use super::{tile::DisplayWidth, Map, MapConversionErr};
+use crate::geometry::{Direction, Point};
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    fmt::{self, Write as _},
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
+#[cfg(feature = "map-render")]
+use {
+    super::{tile::ToRgb, RenderError, Style},
+    std::path::Path,
+};
+
+/// A sparse, unbounded 2d map of tiles.
+///
+/// Every point in the plane holds a tile; points which have never been written hold the
+/// default tile supplied at construction. Only explicitly written points are stored, so this
+/// is appropriate when the interesting region grows without bound or is mostly empty.
+///
+/// The map tracks the bounding box of its stored points. Methods which depend on bounds,
+/// such as [`project`][SparseMap::project] and the `Display` implementation, use that box,
+/// so code written against [`Map`] generally works unchanged.
+///
+/// Iteration visits every point in the bounding box in the same order as `Map`; use
+/// [`iter_stored`][SparseMap::iter_stored] to visit only the stored points.
+///
+/// ## Example
+///
+/// ```
+/// # use aoclib::geometry::{map::SparseMap, tile::Bool, Point};
+/// let mut map = SparseMap::new(Bool::False);
+/// map[Point::new(-5, 3)] = Bool::True;
+/// map[Point::new(1000, -2)] = Bool::True;
+/// assert_eq!(map.len(), 2);
+/// assert_eq!(map[Point::new(7, 7)], Bool::False);
+/// assert_eq!(map.width(), 1006);
+/// assert_eq!(map.height(), 6);
+/// ```
+#[derive(Clone, Debug)]
+pub struct SparseMap<Tile> {
+    tiles: HashMap<Point, Tile>,
+    default: Tile,
+    // inclusive (min, max) corners of the stored points
+    bounds: Option<(Point, Point)>,
+}
+
+impl<Tile: Default> Default for SparseMap<Tile> {
+    fn default() -> Self {
+        SparseMap::new(Tile::default())
+    }
+}
+
+impl<Tile> SparseMap<Tile> {
+    /// Create a new, empty map, in which every point holds `default`.
+    pub fn new(default: Tile) -> SparseMap<Tile> {
+        SparseMap {
+            tiles: HashMap::new(),
+            default,
+            bounds: None,
+        }
+    }
+
+    /// The tile held by every point which has not been written.
+    pub fn default_tile(&self) -> &Tile {
+        &self.default
+    }
+
+    /// Number of stored points.
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    /// `true` when no points are stored.
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    /// `true` when a tile is stored at this point.
+    pub fn contains(&self, point: Point) -> bool {
+        self.tiles.contains_key(&point)
+    }
+
+    /// Get the tile at a point.
+    ///
+    /// This is the default tile unless a tile has been stored there.
+    pub fn get(&self, point: Point) -> &Tile {
+        self.tiles.get(&point).unwrap_or(&self.default)
+    }
+
+    /// Store a tile at a point, returning the tile previously stored there.
+    pub fn insert(&mut self, point: Point, tile: Tile) -> Option<Tile> {
+        self.include(point);
+        self.tiles.insert(point, tile)
+    }
+
+    /// Remove the tile stored at a point, so that it reverts to the default tile.
+    ///
+    /// The bounding box shrinks if necessary; that costs `O(n)` when the point was on its edge.
+    pub fn remove(&mut self, point: Point) -> Option<Tile> {
+        let removed = self.tiles.remove(&point);
+        if removed.is_some() {
+            if let Some((min, max)) = self.bounds {
+                if point.x == min.x || point.y == min.y || point.x == max.x || point.y == max.y {
+                    self.recompute_bounds();
+                }
+            }
+        }
+        removed
+    }
+
+    /// Retain only those stored tiles for which the predicate returns `true`.
+    pub fn retain(&mut self, mut keep: impl FnMut(Point, &mut Tile) -> bool) {
+        self.tiles.retain(|&point, tile| keep(point, tile));
+        self.recompute_bounds();
+    }
+
+    fn include(&mut self, point: Point) {
+        self.bounds = Some(match self.bounds {
+            None => (point, point),
+            Some((min, max)) => (
+                Point::new(min.x.min(point.x), min.y.min(point.y)),
+                Point::new(max.x.max(point.x), max.y.max(point.y)),
+            ),
+        });
+    }
+
+    fn recompute_bounds(&mut self) {
+        self.bounds = None;
+        let points: Vec<_> = self.tiles.keys().copied().collect();
+        for point in points {
+            self.include(point);
+        }
+    }
+
+    /// Width of the bounding box of the stored points.
+    pub fn width(&self) -> usize {
+        self.bounds
+            .map(|(min, max)| (max.x - min.x + 1) as usize)
+            .unwrap_or_default()
+    }
+
+    /// Height of the bounding box of the stored points.
+    pub fn height(&self) -> usize {
+        self.bounds
+            .map(|(min, max)| (max.y - min.y + 1) as usize)
+            .unwrap_or_default()
+    }
+
+    /// Lower left corner of the bounding box, analogous to [`Map::offset`].
+    pub fn offset(&self) -> Point {
+        self.bottom_left()
+    }
+
+    /// Lowest x coordinate of any stored point.
+    pub fn low_x(&self) -> i32 {
+        self.bounds.map(|(min, _)| min.x).unwrap_or_default()
+    }
+
+    /// Highest x coordinate of any stored point.
+    ///
+    /// Note that this is inclusive; use `..=` when using this to bound a range.
+    pub fn high_x(&self) -> i32 {
+        self.bounds.map(|(_, max)| max.x).unwrap_or(-1)
+    }
+
+    /// Lowest y coordinate of any stored point.
+    pub fn low_y(&self) -> i32 {
+        self.bounds.map(|(min, _)| min.y).unwrap_or_default()
+    }
+
+    /// Highest y coordinate of any stored point.
+    ///
+    /// Note that this is inclusive; use `..=` when using this to bound a range.
+    pub fn high_y(&self) -> i32 {
+        self.bounds.map(|(_, max)| max.y).unwrap_or(-1)
+    }
+
+    /// The coordinates of the bottom left corner of the bounding box.
+    pub fn bottom_left(&self) -> Point {
+        Point::new(self.low_x(), self.low_y())
+    }
+
+    /// The coordinates of the top left corner of the bounding box.
+    pub fn top_left(&self) -> Point {
+        Point::new(self.low_x(), self.high_y())
+    }
+
+    /// The coordinates of the bottom right corner of the bounding box.
+    pub fn bottom_right(&self) -> Point {
+        Point::new(self.high_x(), self.low_y())
+    }
+
+    /// The coordinates of the top right corner of the bounding box.
+    pub fn top_right(&self) -> Point {
+        Point::new(self.high_x(), self.high_y())
+    }
+
+    /// `true` when a point is within the bounding box of the stored points.
+    pub fn in_bounds(&self, point: Point) -> bool {
+        self.make_in_bounds()(point)
+    }
+
+    /// Make a function which returns `true` when the parameter is within the bounding box,
+    /// without borrowing `self`.
+    pub fn make_in_bounds(&self) -> impl Fn(Point) -> bool {
+        let bounds = self.bounds;
+        move |point| {
+            bounds.is_some_and(|(min, max)| {
+                point.x >= min.x && point.y >= min.y && point.x <= max.x && point.y <= max.y
+            })
+        }
+    }
+
+    /// Iterate over every point in the bounding box and its tile, in the same order as
+    /// [`Map::iter`].
+    ///
+    /// Points which have not been written yield the default tile.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &Tile)> {
+        self.points().map(move |point| (point, self.get(point)))
+    }
+
+    /// Iterate over every point in the bounding box, in the same order as [`Map::points`].
+    ///
+    /// That is, left to right along each row, from the bottom row to the top.
+    pub fn points(&self) -> impl '_ + Iterator<Item = Point> {
+        (self.low_y()..=self.high_y())
+            .flat_map(move |y| (self.low_x()..=self.high_x()).map(move |x| Point::new(x, y)))
+    }
+
+    /// Iterate over the stored points and their tiles, in arbitrary order.
+    pub fn iter_stored(&self) -> impl Iterator<Item = (Point, &Tile)> {
+        self.tiles.iter().map(|(&point, tile)| (point, tile))
+    }
+
+    /// Iterate over the stored points, with mutable access to their tiles, in arbitrary order.
+    pub fn iter_stored_mut(&mut self) -> impl Iterator<Item = (Point, &mut Tile)> {
+        self.tiles.iter_mut().map(|(&point, tile)| (point, tile))
+    }
+
+    /// Iterate over the stored points, in arbitrary order.
+    pub fn stored_points(&self) -> impl '_ + Iterator<Item = Point> {
+        self.tiles.keys().copied()
+    }
+
+    /// Return an iterator of all points adjacent to the given point.
+    ///
+    /// The map is unbounded, so this always returns 8 elements; it includes diagonals.
+    pub fn adjacencies(&self, point: Point) -> impl Iterator<Item = Point> {
+        self.orthogonal_adjacencies(point).chain(
+            Direction::iter_diag().map(move |(vertical, horizontal)| point + vertical + horizontal),
+        )
+    }
+
+    /// Return an iterator of all points orthogonally adjacent to the given point.
+    ///
+    /// The map is unbounded, so this always returns 4 elements; it does not include diagonals.
+    pub fn orthogonal_adjacencies(&self, point: Point) -> impl Iterator<Item = Point> {
+        Direction::iter().map(move |direction| point + direction)
+    }
+
+    /// Equivalent to [`adjacencies`][Self::adjacencies]; provided for parity with [`Map`].
+    pub fn make_adjacencies(&self, point: Point) -> impl Iterator<Item = Point> {
+        self.adjacencies(point)
+    }
+
+    /// Equivalent to [`orthogonal_adjacencies`][Self::orthogonal_adjacencies]; provided for parity with [`Map`].
+    pub fn make_orthogonal_adjacencies(&self, point: Point) -> impl Iterator<Item = Point> {
+        self.orthogonal_adjacencies(point)
+    }
+
+    /// Return an iterator of points arrived at by applying the given deltas to the origin,
+    /// while they remain within the bounding box.
+    ///
+    /// The origin point is always the first item in this iteration.
+    pub fn project(&self, origin: Point, dx: i32, dy: i32) -> impl Iterator<Item = Point> {
+        let in_bounds = self.make_in_bounds();
+        std::iter::successors(Some(origin), move |&current| Some(current + (dx, dy)))
+            .take_while(move |&point| in_bounds(point))
+    }
+
+    /// Translate all points in this map by a given amount.
+    ///
+    /// Completes in `O(n)`.
+    pub fn translate(&mut self, dx: i32, dy: i32) {
+        self.tiles = self
+            .tiles
+            .drain()
+            .map(|(point, tile)| (point + (dx, dy), tile))
+            .collect();
+        self.bounds = self
+            .bounds
+            .map(|(min, max)| (min + (dx, dy), max + (dx, dy)));
+    }
+
+    /// Convert the underlying tile type of a map.
+    pub fn convert_tile_type<NewTile>(self) -> SparseMap<NewTile>
+    where
+        Tile: Into<NewTile>,
+    {
+        SparseMap {
+            tiles: self
+                .tiles
+                .into_iter()
+                .map(|(point, tile)| (point, tile.into()))
+                .collect(),
+            default: self.default.into(),
+            bounds: self.bounds,
+        }
+    }
+}
+
+impl<Tile: Clone> SparseMap<Tile> {
+    /// Get a mutable reference to the tile at a point, storing a copy of the default tile there if necessary.
+    pub fn get_mut(&mut self, point: Point) -> &mut Tile {
+        self.include(point);
+        let default = &self.default;
+        self.tiles.entry(point).or_insert_with(|| default.clone())
+    }
+
+    /// Iterate over every point in the bounding box, with mutable access to its tile, in the
+    /// same order as [`Map::iter_mut`].
+    ///
+    /// This stores a copy of the default tile at every point in the bounding box which has not
+    /// been written, so it costs as much memory as a dense map.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Point, &mut Tile)> {
+        let points: Vec<_> = self.points().collect();
+        for &point in &points {
+            if !self.tiles.contains_key(&point) {
+                self.tiles.insert(point, self.default.clone());
+            }
+        }
+        let mut tiles: Vec<_> = self
+            .tiles
+            .iter_mut()
+            .map(|(&point, tile)| (point, tile))
+            .collect();
+        tiles.sort_unstable_by_key(|&(point, _)| (point.y, point.x));
+        tiles.into_iter()
+    }
+
+    /// Produce a dense [`Map`] covering the bounding box of this map.
+    pub fn to_map(&self) -> Map<Tile> {
+        Map::procedural_offset(self.offset(), self.width(), self.height(), |point| {
+            self.get(point).clone()
+        })
+    }
+}
+
+impl<Tile: PartialEq> SparseMap<Tile> {
+    /// Produce a sparse map from a dense one, storing only those tiles which differ from `default`.
+    pub fn from_map(map: Map<Tile>, default: Tile) -> SparseMap<Tile> {
+        let mut sparse = SparseMap::new(default);
+        let points: Vec<_> = map.points().collect();
+        for (point, tile) in points.into_iter().zip(map.into_tiles()) {
+            if tile != sparse.default {
+                sparse.insert(point, tile);
+            }
+        }
+        sparse
+    }
+}
+
+impl<Tile: PartialEq> PartialEq for SparseMap<Tile> {
+    /// Two sparse maps are equal when they have the same bounding box and hold the same tile at
+    /// every point.
+    ///
+    /// Storing a default tile explicitly does not otherwise affect equality, but it does extend
+    /// the bounding box, and so the dimensions, iteration, and display of the map.
+    fn eq(&self, other: &Self) -> bool {
+        self.default == other.default
+            && self.bounds == other.bounds
+            && self
+                .iter_stored()
+                .all(|(point, tile)| tile == other.get(point))
+            && other
+                .iter_stored()
+                .all(|(point, tile)| tile == self.get(point))
+    }
+}
+
+impl<Tile: Eq> Eq for SparseMap<Tile> {}
+
+impl<Tile> Index<Point> for SparseMap<Tile> {
+    type Output = Tile;
+
+    fn index(&self, point: Point) -> &Tile {
+        self.get(point)
+    }
+}
+
+impl<Tile: Clone> IndexMut<Point> for SparseMap<Tile> {
+    /// Stores a copy of the default tile at this point if necessary.
+    fn index_mut(&mut self, point: Point) -> &mut Tile {
+        self.get_mut(point)
+    }
+}
+
+impl<Tile: Default + PartialEq> From<Map<Tile>> for SparseMap<Tile> {
+    /// Store only those tiles which differ from `Tile::default()`.
+    fn from(map: Map<Tile>) -> Self {
+        SparseMap::from_map(map, Tile::default())
+    }
+}
+
+impl<Tile: Clone> From<&SparseMap<Tile>> for Map<Tile> {
+    fn from(sparse: &SparseMap<Tile>) -> Self {
+        sparse.to_map()
+    }
+}
+
+impl<Tile> TryFrom<&str> for SparseMap<Tile>
+where
+    Tile: Clone + Default + PartialEq + DisplayWidth + FromStr,
+    <Tile as FromStr>::Err: 'static + std::error::Error + Send + Sync,
+{
+    type Error = MapConversionErr;
+
+    /// The input should be in natural graphical order: its first characters are the top left.
+    ///
+    /// Tiles equal to `Tile::default()` are not stored.
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        <Map<Tile> as TryFrom<&str>>::try_from(input).map(Into::into)
+    }
+}
+
+impl<Tile> fmt::Display for SparseMap<Tile>
+where
+    Tile: fmt::Display + DisplayWidth,
+{
+    /// Displays the bounding box of the stored points.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_string_with_override(|_, _| None))
+    }
+}
+
+impl<Tile> SparseMap<Tile>
+where
+    Tile: fmt::Display + DisplayWidth,
+{
+    /// Produce a string from the bounding box of this `SparseMap`, with certain tiles overridden.
+    ///
+    /// This does not edit the map, just adjusts its presentation.
+    ///
+    /// ## Panics
+    ///
+    /// - If an override value's returned string is not the same length as `Tile::DISPLAY_WIDTH`.
+    pub fn to_string_with_override(
+        &self,
+        override_tiles: impl Fn(Point, &Tile) -> Option<String>,
+    ) -> String {
+        let mut out =
+            String::with_capacity((self.width() * Tile::DISPLAY_WIDTH + 1) * self.height());
+        for y in (self.low_y()..=self.high_y()).rev() {
+            for x in self.low_x()..=self.high_x() {
+                let point = Point::new(x, y);
+                let tile = self.get(point);
+                match override_tiles(point, tile) {
+                    Some(override_str) => {
+                        assert_eq!(
+                            override_str.len(),
+                            Tile::DISPLAY_WIDTH,
+                            "override string length must match display width"
+                        );
+                        out.push_str(&override_str);
+                    }
+                    None => write!(&mut out, "{:width$}", tile, width = Tile::DISPLAY_WIDTH)
+                        .expect("writing to a string always succeeds"),
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+#[cfg(feature = "map-render")]
+impl<Tile> SparseMap<Tile>
+where
+    Tile: Clone + ToRgb,
+{
+    /// Render the bounding box of this map as a still image into an output file.
+    ///
+    /// _Depends on the `map-render` feature._
+    ///
+    /// See [`Map::render`].
+    pub fn render(&self, output: &Path, style: Style) -> Result<(), RenderError> {
+        self.to_map().render(output, style)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::map::tile::Bool;
+
+    #[test]
+    fn test_bounds_track_writes_and_removals() {
+        let mut map = SparseMap::new(0);
+        assert_eq!((map.width(), map.height()), (0, 0));
+        map.insert(Point::new(-2, 1), 1);
+        map[Point::new(3, 4)] += 5;
+        assert_eq!(map.bottom_left(), Point::new(-2, 1));
+        assert_eq!(map.top_right(), Point::new(3, 4));
+        map.remove(Point::new(3, 4));
+        assert_eq!(map.top_right(), Point::new(-2, 1));
+        assert!(map
+            .project(Point::new(-2, 1), 1, 0)
+            .eq(Some(Point::new(-2, 1))));
+    }
+
+    #[test]
+    fn test_round_trip_through_map() {
+        let text = "#..\n.#.\n..#\n";
+        let sparse = <SparseMap<Bool> as TryFrom<&str>>::try_from(text).unwrap();
+        assert_eq!(sparse.len(), 3);
+        assert_eq!(sparse.to_string(), text);
+        let dense = sparse.to_map();
+        assert_eq!(dense.to_string(), text);
+        assert_eq!(SparseMap::from(dense), sparse);
+    }
+
+    #[test]
+    fn test_display_with_override() {
+        let mut map = SparseMap::new(Bool::False);
+        map.insert(Point::new(10, 10), Bool::True);
+        map.insert(Point::new(12, 11), Bool::True);
+        assert_eq!(
+            map.to_string_with_override(
+                |point, _| (point == Point::new(11, 10)).then(|| "@".to_string())
+            ),
+            "..#\n#@.\n"
+        );
+    }
+
+    #[test]
+    fn test_iteration_matches_map() {
+        let text = "#..\n.#.\n..#\n";
+        let mut sparse = <SparseMap<Bool> as TryFrom<&str>>::try_from(text).unwrap();
+        let dense = sparse.to_map();
+        assert!(sparse.points().eq(dense.points()));
+        assert!(sparse.iter().eq(dense.iter()));
+        assert_eq!(sparse.iter_stored().count(), 3);
+
+        for (point, tile) in sparse.iter_mut() {
+            *tile = Bool::from(point.x == 0);
+        }
+        assert_eq!(sparse.len(), 9);
+        assert_eq!(sparse.to_string(), "#..\n#..\n#..\n");
+    }
+
+    #[test]
+    fn test_equality_of_stored_defaults() {
+        let mut left = SparseMap::new(Bool::False);
+        left.insert(Point::new(0, 0), Bool::True);
+        left.insert(Point::new(2, 2), Bool::True);
+        let mut right = left.clone();
+        right.insert(Point::new(1, 1), Bool::False);
+        assert_eq!(left, right);
+
+        // a default beyond the bounding box changes how the map is displayed
+        right.insert(Point::new(3, 0), Bool::False);
+        assert_ne!(left.to_string(), right.to_string());
+        assert_ne!(left, right);
+    }
+}
@@ -0,0 +1,381 @@
+use super::{ContextInto, Map};
+use crate::geometry::{Direction, Point};
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+/// How a visitor is allowed to change course, for [`Map::navigate_steering`].
+///
+/// A visitor always has a heading, and keeps count of how many tiles it has moved since it last
+/// turned: its run. It can always move forward, unless its run has reached `max_run`. It can only
+/// turn once its run has reached `min_run`; every turn is immediately followed by a move forward.
+/// It can only stop at its destination once its run has reached `min_run`.
+///
+/// ## Example
+///
+/// Some crucibles must move at least 4 and at most 10 tiles before turning:
+///
+/// ```
+/// # use aoclib::geometry::map::Steering;
+/// let ultra_crucible = Steering {
+///     min_run: 4,
+///     max_run: Some(10),
+///     ..Steering::default()
+/// };
+/// ```
+///
+/// Some reindeer can move in any direction, but spend 1000 points to turn:
+///
+/// ```
+/// # use aoclib::geometry::map::Steering;
+/// let reindeer = Steering {
+///     turn_cost: 1000,
+///     u_turns: true,
+///     ..Steering::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Steering {
+    /// Cost of turning 90 degrees, in addition to the cost of the subsequent move.
+    pub turn_cost: u32,
+    /// Minimum run before the visitor can turn or stop.
+    pub min_run: u32,
+    /// Maximum run; `None` when the visitor can move forward indefinitely.
+    pub max_run: Option<u32>,
+    /// Whether the visitor can reverse its heading, by way of two turns.
+    pub u_turns: bool,
+}
+
+impl Default for Steering {
+    /// Turns are free and unconstrained, but the visitor can never reverse.
+    fn default() -> Self {
+        Steering {
+            turn_cost: 0,
+            min_run: 0,
+            max_run: None,
+            u_turns: false,
+        }
+    }
+}
+
+impl Steering {
+    /// Largest run which needs to be distinguished from longer runs.
+    fn run_cap(&self) -> u32 {
+        self.max_run.unwrap_or(self.min_run)
+    }
+}
+
+/// One element of a path produced by [`Map::navigate_steering`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, parse_display::Display)]
+pub enum Maneuver {
+    /// Turn 90 degrees counterclockwise in place.
+    #[display("L")]
+    TurnLeft,
+    /// Turn 90 degrees clockwise in place.
+    #[display("R")]
+    TurnRight,
+    /// Move one tile along the current heading.
+    #[display("F")]
+    Forward,
+}
+
+/// Ways to change heading before moving forward.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Turn {
+    Straight,
+    Left,
+    Right,
+    Reverse,
+}
+
+impl Turn {
+    const ALL: [Turn; 4] = [Turn::Straight, Turn::Left, Turn::Right, Turn::Reverse];
+
+    fn apply(self, heading: Direction) -> Direction {
+        match self {
+            Turn::Straight => heading,
+            Turn::Left => heading.turn_left(),
+            Turn::Right => heading.turn_right(),
+            Turn::Reverse => heading.reverse(),
+        }
+    }
+
+    fn count(self) -> u32 {
+        match self {
+            Turn::Straight => 0,
+            Turn::Left | Turn::Right => 1,
+            Turn::Reverse => 2,
+        }
+    }
+
+    fn maneuvers(self) -> &'static [Maneuver] {
+        match self {
+            Turn::Straight => &[Maneuver::Forward],
+            Turn::Left => &[Maneuver::TurnLeft, Maneuver::Forward],
+            Turn::Right => &[Maneuver::TurnRight, Maneuver::Forward],
+            Turn::Reverse => &[Maneuver::TurnLeft, Maneuver::TurnLeft, Maneuver::Forward],
+        }
+    }
+}
+
+fn heading_index(heading: Direction) -> usize {
+    match heading {
+        Direction::Up => 0,
+        Direction::Down => 1,
+        Direction::Left => 2,
+        Direction::Right => 3,
+    }
+}
+
+/// A search state: where the visitor is, which way it faces, and how far it has gone straight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Pose {
+    position: Point,
+    heading: Direction,
+    run: u32,
+}
+
+impl<Tile> Map<Tile>
+where
+    Tile: Clone + ContextInto<Option<u32>, Context = ()>,
+{
+    /// Navigate between the given points, where the visitor's course is constrained.
+    ///
+    /// See [`navigate_steering_ctx`][Self::navigate_steering_ctx].
+    pub fn navigate_steering(
+        &self,
+        steering: &Steering,
+        from: Point,
+        heading: Option<Direction>,
+        to: Point,
+    ) -> Option<(u32, Vec<Maneuver>)> {
+        self.navigate_steering_ctx(&(), steering, from, heading, to)
+    }
+}
+
+impl<Tile: Clone + ContextInto<Option<u32>>> Map<Tile> {
+    /// Navigate between the given points, where the visitor's course is constrained.
+    ///
+    /// Each tile converts into the cost of stepping onto it; `None` means the tile is impassable.
+    /// Turning costs extra, as described by `steering`. The visitor starts at `from` facing
+    /// `heading`; if that is `None`, it may start facing any direction.
+    ///
+    /// Returns the total cost of the cheapest path, and the turns and moves along it.
+    pub fn navigate_steering_ctx(
+        &self,
+        context: &<Tile as ContextInto<Option<u32>>>::Context,
+        steering: &Steering,
+        from: Point,
+        heading: Option<Direction>,
+        to: Point,
+    ) -> Option<(u32, Vec<Maneuver>)> {
+        let resolve = self.make_resolve();
+        let from = resolve(from)?;
+        let to = resolve(to)?;
+
+        let run_cap = steering.run_cap();
+        let runs = run_cap as usize + 1;
+        let state_index = |pose: Pose| {
+            (self.point_index(pose.position) * 4 + heading_index(pose.heading)) * runs
+                + pose.run as usize
+        };
+        let n_states = self.width() * self.height() * 4 * runs;
+
+        let mut cheapest_path_cost = vec![u32::MAX; n_states];
+        // the previous pose, and the turn made before moving forward from it
+        let mut came_from: Vec<Option<(Pose, Turn)>> = vec![None; n_states];
+        let mut open_set = BinaryHeap::new();
+
+        let initial_headings = match heading {
+            Some(heading) => vec![heading],
+            None => Direction::iter().collect(),
+        };
+        for heading in initial_headings {
+            let pose = Pose {
+                position: from,
+                heading,
+                run: 0,
+            };
+            cheapest_path_cost[state_index(pose)] = 0;
+            open_set.push(Reverse((0, state_index(pose))));
+        }
+
+        // inverse of `state_index`
+        let pose_of = |index: usize| {
+            let run = (index % runs) as u32;
+            let index = index / runs;
+            let heading = [
+                Direction::Up,
+                Direction::Down,
+                Direction::Left,
+                Direction::Right,
+            ][index % 4];
+            let position = self.index2point(index / 4);
+            Pose {
+                position,
+                heading,
+                run,
+            }
+        };
+
+        while let Some(Reverse((cost, index))) = open_set.pop() {
+            if cost > cheapest_path_cost[index] {
+                continue;
+            }
+            let pose = pose_of(index);
+
+            if pose.position == to && pose.run >= steering.min_run {
+                let mut maneuvers = Vec::new();
+                let mut current = pose;
+                while let Some((previous, turn)) = came_from[state_index(current)] {
+                    maneuvers.extend(turn.maneuvers().iter().rev());
+                    current = previous;
+                }
+                maneuvers.reverse();
+                return Some((cost, maneuvers));
+            }
+
+            for turn in Turn::ALL {
+                let allowed = match turn {
+                    Turn::Straight => steering.max_run.is_none_or(|max| pose.run < max),
+                    Turn::Left | Turn::Right => pose.run >= steering.min_run,
+                    Turn::Reverse => steering.u_turns && pose.run >= steering.min_run,
+                };
+                if !allowed {
+                    continue;
+                }
+
+                let heading = turn.apply(pose.heading);
+                let position = match resolve(pose.position + heading) {
+                    Some(position) => position,
+                    None => continue,
+                };
+                let step_cost = match self[position].clone().ctx_into(position, context) {
+                    Some(step_cost) => step_cost,
+                    None => continue,
+                };
+                let run = match turn {
+                    Turn::Straight => pose.run + 1,
+                    _ => 1,
+                }
+                .min(run_cap);
+                let next = Pose {
+                    position,
+                    heading,
+                    run,
+                };
+
+                let tentative_cost = cost
+                    .saturating_add(steering.turn_cost.saturating_mul(turn.count()))
+                    .saturating_add(step_cost);
+                let next_index = state_index(next);
+                if tentative_cost < cheapest_path_cost[next_index] {
+                    cheapest_path_cost[next_index] = tentative_cost;
+                    came_from[next_index] = Some((pose, turn));
+                    open_set.push(Reverse((tentative_cost, next_index)));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::map::tile::Digit;
+    use std::convert::TryFrom;
+
+    const CRUCIBLE: &str = "2413432311323\n3215453535623\n3255245654254\n3446585845452\n\
+        4546657867536\n1438598798454\n4457876987766\n3637877979653\n4654967986887\n\
+        4564679986453\n1224686865563\n2546548887735\n4322674655533\n";
+
+    /// Follow a path of maneuvers, producing the final position.
+    fn follow(mut position: Point, mut heading: Direction, maneuvers: &[Maneuver]) -> Point {
+        for maneuver in maneuvers {
+            match maneuver {
+                Maneuver::TurnLeft => heading = heading.turn_left(),
+                Maneuver::TurnRight => heading = heading.turn_right(),
+                Maneuver::Forward => position += heading,
+            }
+        }
+        position
+    }
+
+    #[test]
+    fn test_crucibles() {
+        let map = <Map<Digit> as TryFrom<&str>>::try_from(CRUCIBLE).unwrap();
+        let (from, to) = (map.top_left(), map.bottom_right());
+
+        let crucible = Steering {
+            max_run: Some(3),
+            ..Steering::default()
+        };
+        let (cost, _) = map.navigate_steering(&crucible, from, None, to).unwrap();
+        assert_eq!(cost, 102);
+
+        let ultra_crucible = Steering {
+            min_run: 4,
+            max_run: Some(10),
+            ..Steering::default()
+        };
+        let (cost, maneuvers) = map
+            .navigate_steering(&ultra_crucible, from, Some(Direction::Right), to)
+            .unwrap();
+        assert_eq!(cost, 94);
+        assert_eq!(follow(from, Direction::Right, &maneuvers), to);
+
+        // without constraints, this is just weighted navigation
+        let (cost, _) = map
+            .navigate_steering(&Steering::default(), from, None, to)
+            .unwrap();
+        assert_eq!(cost, map.navigate_weighted(from, to).unwrap().0);
+    }
+
+    #[test]
+    fn test_reindeer() {
+        let maze = [
+            "###############",
+            "#.......#....E#",
+            "#.#.###.#.###.#",
+            "#.....#.#...#.#",
+            "#.###.#####.#.#",
+            "#.#.#.......#.#",
+            "#.#.#####.###.#",
+            "#...........#.#",
+            "###.#.#####.#.#",
+            "#...#.....#.#.#",
+            "#.#.#.###.#.#.#",
+            "#.....#...#.#.#",
+            "#.###.#.#.#.#.#",
+            "#S..#.....#...#",
+            "###############",
+        ];
+        let height = maze.len() as i32;
+        let tile =
+            |point: Point| maze[(height - 1 - point.y) as usize].as_bytes()[point.x as usize];
+        let map = Map::procedural(maze[0].len(), maze.len(), |point| match tile(point) {
+            b'#' => None,
+            _ => Some(1),
+        });
+        let find = |target| map.points().find(|&point| tile(point) == target).unwrap();
+        let (from, to) = (find(b'S'), find(b'E'));
+
+        let reindeer = Steering {
+            turn_cost: 1000,
+            u_turns: true,
+            ..Steering::default()
+        };
+        let (cost, maneuvers) = map
+            .navigate_steering(&reindeer, from, Some(Direction::Right), to)
+            .unwrap();
+        assert_eq!(cost, 7036);
+        assert_eq!(follow(from, Direction::Right, &maneuvers), to);
+
+        // starting the wrong way round costs two turns
+        let (cost, maneuvers) = map
+            .navigate_steering(&reindeer, from, Some(Direction::Down), to)
+            .unwrap();
+        assert_eq!(cost, 8036);
+        assert_eq!(&maneuvers[..2], &[Maneuver::TurnLeft, Maneuver::TurnLeft]);
+    }
+}
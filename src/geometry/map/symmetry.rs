@@ -0,0 +1,291 @@
+use super::Map;
+use crate::geometry::Point;
+
+/// One of the eight ways to rotate and reflect a rectangle onto itself.
+///
+/// Transformations of a [`Map`] keep its offset: they rearrange the tiles within the map, and
+/// the lower left corner stays where it was. Those which swap the axes also swap the width and
+/// height.
+///
+/// ## Example
+///
+/// ```
+/// # use aoclib::geometry::{map::Symmetry, Map, Point};
+/// let map = Map::procedural(3, 2, |point| point.x + 3 * point.y);
+/// let transposed = map.transform(Symmetry::Transpose);
+/// assert_eq!((transposed.width(), transposed.height()), (2, 3));
+/// assert_eq!(transposed[Point::new(1, 2)], map[Point::new(2, 1)]);
+///
+/// // each orientation of this map is distinct
+/// let orientations: Vec<_> = map.symmetries().map(|(_, map)| map).collect();
+/// assert_eq!(orientations.len(), 8);
+/// assert!((1..8).all(|i| !orientations[..i].contains(&orientations[i])));
+/// ```
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Default,
+    parse_display::Display,
+    parse_display::FromStr,
+)]
+#[display(style = "snake_case")]
+pub enum Symmetry {
+    /// Leave every tile where it is.
+    #[default]
+    Identity,
+    /// Rotate 90 degrees counterclockwise.
+    RotateLeft,
+    /// Rotate 180 degrees.
+    RotateHalf,
+    /// Rotate 90 degrees clockwise.
+    RotateRight,
+    /// Reflect across a vertical axis, exchanging left and right.
+    FlipHorizontal,
+    /// Reflect across a horizontal axis, exchanging top and bottom.
+    FlipVertical,
+    /// Reflect across the diagonal through the lower left corner, exchanging `x` and `y`.
+    Transpose,
+    /// Reflect across the diagonal through the upper left corner.
+    AntiTranspose,
+}
+
+impl Symmetry {
+    /// Iterate over all eight symmetries, starting with [`Symmetry::Identity`].
+    pub fn iter() -> impl Iterator<Item = Symmetry> {
+        use Symmetry::*;
+        [
+            Identity,
+            RotateLeft,
+            RotateHalf,
+            RotateRight,
+            FlipHorizontal,
+            FlipVertical,
+            Transpose,
+            AntiTranspose,
+        ]
+        .iter()
+        .copied()
+    }
+
+    /// `true` if this symmetry exchanges the horizontal and vertical axes.
+    pub fn swaps_axes(self) -> bool {
+        matches!(
+            self,
+            Symmetry::RotateLeft
+                | Symmetry::RotateRight
+                | Symmetry::Transpose
+                | Symmetry::AntiTranspose
+        )
+    }
+
+    /// The symmetry which undoes this one.
+    pub fn inverse(self) -> Symmetry {
+        match self {
+            Symmetry::RotateLeft => Symmetry::RotateRight,
+            Symmetry::RotateRight => Symmetry::RotateLeft,
+            other => other,
+        }
+    }
+
+    /// Find where a point ends up when a rectangle of the given size is transformed.
+    ///
+    /// Points are relative to the lower left corner of the rectangle, which stays at the origin.
+    pub fn transform_point(self, point: Point, width: usize, height: usize) -> Point {
+        let (w, h) = (width as i32 - 1, height as i32 - 1);
+        let Point { x, y } = point;
+        match self {
+            Symmetry::Identity => Point::new(x, y),
+            Symmetry::RotateLeft => Point::new(h - y, x),
+            Symmetry::RotateHalf => Point::new(w - x, h - y),
+            Symmetry::RotateRight => Point::new(y, w - x),
+            Symmetry::FlipHorizontal => Point::new(w - x, y),
+            Symmetry::FlipVertical => Point::new(x, h - y),
+            Symmetry::Transpose => Point::new(y, x),
+            Symmetry::AntiTranspose => Point::new(h - y, w - x),
+        }
+    }
+}
+
+impl<Tile: Clone> Map<Tile> {
+    /// Create a copy of this map which has been transformed by a symmetry.
+    pub fn transform(&self, symmetry: Symmetry) -> Map<Tile> {
+        let (width, height) = if symmetry.swaps_axes() {
+            (self.height(), self.width())
+        } else {
+            (self.width(), self.height())
+        };
+        // we fill in every tile of the output by pulling from the input
+        let inverse = symmetry.inverse();
+        Map::procedural_offset(self.offset(), width, height, |point| {
+            let source =
+                inverse.transform_point(point - self.offset(), width, height) + self.offset();
+            self[source].clone()
+        })
+        .with_topology(self.topology())
+    }
+
+    /// Create a copy of this map with its axes exchanged.
+    ///
+    /// The tile at `(x, y)` moves to `(y, x)`, relative to the offset.
+    pub fn transpose(&self) -> Map<Tile> {
+        self.transform(Symmetry::Transpose)
+    }
+
+    /// Iterate over all eight orientations of this map, identified by the symmetry which
+    /// produces each.
+    ///
+    /// Symmetric maps produce some orientations more than once.
+    pub fn symmetries(&self) -> impl '_ + Iterator<Item = (Symmetry, Map<Tile>)> {
+        Symmetry::iter().map(move |symmetry| (symmetry, self.transform(symmetry)))
+    }
+}
+
+impl<Tile> Map<Tile> {
+    /// Flip this map vertically without allocating; the axis of symmetry is horizontal.
+    pub fn flip_vertical_in_place(&mut self) {
+        let low_y = self.low_y();
+        let high_y = self.high_y();
+        for dy in 0..self.height() as i32 / 2 {
+            for x in self.low_x()..=self.high_x() {
+                self.swap(Point::new(x, low_y + dy), Point::new(x, high_y - dy));
+            }
+        }
+    }
+
+    /// Flip this map horizontally without allocating; the axis of symmetry is vertical.
+    pub fn flip_horizontal_in_place(&mut self) {
+        let low_x = self.low_x();
+        let high_x = self.high_x();
+        for y in self.low_y()..=self.high_y() {
+            for dx in 0..self.width() as i32 / 2 {
+                self.swap(Point::new(low_x + dx, y), Point::new(high_x - dx, y));
+            }
+        }
+    }
+
+    /// Transpose this square map without allocating.
+    ///
+    /// ## Panics
+    ///
+    /// If the map is not square.
+    pub fn transpose_in_place(&mut self) {
+        assert_eq!(
+            self.width(),
+            self.height(),
+            "in-place transposition is only legal for square maps"
+        );
+        let offset = self.offset();
+        for y in 0..self.height() as i32 {
+            for x in 0..y {
+                self.swap(offset + Point::new(x, y), offset + Point::new(y, x));
+            }
+        }
+    }
+
+    /// Rotate this square map counterclockwise without allocating.
+    ///
+    /// ## Panics
+    ///
+    /// If the map is not square.
+    pub fn rotate_left_in_place(&mut self) {
+        self.transform_in_place(Symmetry::RotateLeft);
+    }
+
+    /// Rotate this square map clockwise without allocating.
+    ///
+    /// ## Panics
+    ///
+    /// If the map is not square.
+    pub fn rotate_right_in_place(&mut self) {
+        self.transform_in_place(Symmetry::RotateRight);
+    }
+
+    /// Transform this map by a symmetry without allocating.
+    ///
+    /// ## Panics
+    ///
+    /// If the symmetry [swaps axes][Symmetry::swaps_axes] and the map is not square.
+    pub fn transform_in_place(&mut self, symmetry: Symmetry) {
+        // every symmetry is an optional transposition followed by optional flips
+        let (transpose, flip_horizontal, flip_vertical) = match symmetry {
+            Symmetry::Identity => (false, false, false),
+            Symmetry::RotateLeft => (true, true, false),
+            Symmetry::RotateHalf => (false, true, true),
+            Symmetry::RotateRight => (true, false, true),
+            Symmetry::FlipHorizontal => (false, true, false),
+            Symmetry::FlipVertical => (false, false, true),
+            Symmetry::Transpose => (true, false, false),
+            Symmetry::AntiTranspose => (true, true, true),
+        };
+        if transpose {
+            self.transpose_in_place();
+        }
+        if flip_horizontal {
+            self.flip_horizontal_in_place();
+        }
+        if flip_vertical {
+            self.flip_vertical_in_place();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_existing_transformations() {
+        let map = Map::procedural(4, 3, |point| point.x + 4 * point.y);
+        assert_eq!(map.transform(Symmetry::Identity), map);
+        assert_eq!(map.transform(Symmetry::RotateLeft), map.rotate_left());
+        assert_eq!(map.transform(Symmetry::RotateRight), map.rotate_right());
+        assert_eq!(
+            map.transform(Symmetry::RotateHalf),
+            map.rotate_left().rotate_left()
+        );
+        assert_eq!(
+            map.transform(Symmetry::FlipHorizontal),
+            map.flip_horizontal()
+        );
+        assert_eq!(map.transform(Symmetry::FlipVertical), map.flip_vertical());
+        assert_eq!(
+            map.transform(Symmetry::AntiTranspose),
+            map.rotate_left().flip_vertical()
+        );
+
+        for (symmetry, transformed) in map.symmetries() {
+            assert_eq!(transformed.transform(symmetry.inverse()), map);
+        }
+    }
+
+    #[test]
+    fn test_in_place() {
+        let offset = Point::new(-2, 5);
+        for size in 0..6 {
+            let map = Map::procedural_offset(offset, size, size, |point| point.x * 10 + point.y);
+            for symmetry in Symmetry::iter() {
+                let mut in_place = map.clone();
+                in_place.transform_in_place(symmetry);
+                assert_eq!(in_place, map.transform(symmetry), "{} {}", size, symmetry);
+            }
+        }
+
+        // flips work for any shape
+        let map = Map::procedural(5, 2, |point| point.x + 5 * point.y);
+        let mut flipped = map.clone();
+        flipped.transform_in_place(Symmetry::RotateHalf);
+        assert_eq!(flipped, map.transform(Symmetry::RotateHalf));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_transpose_in_place_requires_square() {
+        Map::procedural(3, 2, |_| 0).transpose_in_place();
+    }
+}
@@ -11,7 +11,7 @@ pub trait DisplayWidth {
     const DISPLAY_WIDTH: usize;
 
     /// Split a string into an iterator of chunks of characters of length `DISPLAY_WIDTH`
-    fn chunks(s: &str) -> Chunks<Self> {
+    fn chunks(s: &str) -> Chunks<'_, Self> {
         Chunks(s.chars(), PhantomData)
     }
 }
@@ -152,6 +152,15 @@ impl TryFrom<u8> for Digit {
     }
 }
 
+/// A digit is the cost of entering its tile, for use with [`Map::navigate_weighted`][super::Map::navigate_weighted].
+///
+/// No digit is impassable.
+impl From<Digit> for Option<u32> {
+    fn from(Digit(value): Digit) -> Self {
+        Some(value.into())
+    }
+}
+
 impl ToRgb for Digit {
     fn to_rgb(&self) -> [u8; 3] {
         const STEP: u8 = u8::MAX / 9;
@@ -0,0 +1,12 @@
+/// How a [`Map`][super::Map] treats its edges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Topology {
+    /// The map is a rectangle; points beyond its edges do not exist.
+    #[default]
+    Bounded,
+    /// The map is a torus: moving off one edge re-enters from the opposite edge.
+    ///
+    /// Indexing reduces any point modulo the dimensions of the map, and adjacency,
+    /// projection, and navigation all wrap around the edges.
+    Toroidal,
+}
@@ -1,3 +1,4 @@
+pub mod automaton;
 pub mod direction;
 pub mod hex;
 pub mod line;
@@ -8,5 +9,5 @@ pub mod vector3;
 pub mod vector4;
 
 pub use direction::Direction;
-pub use map::{tile, Map, MapConversionErr};
+pub use map::{tile, GrowingMap, Map, MapConversionErr, SparseMap, Topology};
 pub use point::Point;
@@ -0,0 +1,188 @@
+use super::ScanError;
+
+/// An error produced by a `FromStr` implementation generated by `#[derive(AocParse)]`.
+///
+/// _The derive macro depends on the `derive` feature; this error type is always available._
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("column {column}: {message}")]
+pub struct AocParseError {
+    /// 1-based column in the input, counted in characters, at which parsing failed.
+    pub column: usize,
+    /// Description of the failure.
+    pub message: String,
+}
+
+impl AocParseError {
+    pub fn new(column: usize, message: impl Into<String>) -> AocParseError {
+        AocParseError {
+            column,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<ScanError> for AocParseError {
+    fn from(err: ScanError) -> Self {
+        match err {
+            ScanError::MissingLiteral { literal, column } => {
+                AocParseError::new(column, format!("expected {:?}", literal))
+            }
+            ScanError::TrailingInput { column } => {
+                AocParseError::new(column, "unexpected trailing input")
+            }
+            ScanError::Placeholder { column, source } => {
+                AocParseError::new(column, source.to_string())
+            }
+            err @ ScanError::Arity { .. } => AocParseError::new(1, err.to_string()),
+        }
+    }
+}
+
+/// Support functions for code generated by `#[derive(AocParse)]`.
+///
+/// These are not part of the public API.
+#[doc(hidden)]
+pub mod __private {
+    pub use super::AocParseError;
+    pub use crate::input::{int_strs, ScanPattern};
+    pub use std::sync::OnceLock;
+
+    use std::{fmt::Display, str::FromStr};
+
+    /// 1-based column at which `slice` begins within `input`.
+    ///
+    /// `slice` must be a subslice of `input`; if it is not, this returns 1.
+    pub fn column_of(input: &str, slice: &str) -> usize {
+        let offset = (slice.as_ptr() as usize).wrapping_sub(input.as_ptr() as usize);
+        if !matches!(offset.checked_add(slice.len()), Some(end) if end <= input.len()) {
+            return 1;
+        }
+        match input.get(..offset) {
+            Some(prefix) => prefix.chars().count() + 1,
+            None => 1,
+        }
+    }
+
+    /// Split `input` according to `pattern`.
+    pub fn captures<'a>(
+        pattern: &ScanPattern,
+        input: &'a str,
+    ) -> Result<Vec<&'a str>, AocParseError> {
+        Ok(pattern
+            .captures(input)?
+            .into_iter()
+            .map(|capture| capture.text)
+            .collect())
+    }
+
+    /// Parse the field named `field` from `text`, which must be a subslice of `input`.
+    pub fn parse_field<T>(input: &str, text: &str, field: &str) -> Result<T, AocParseError>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: Display,
+    {
+        text.parse().map_err(|err| {
+            AocParseError::new(
+                column_of(input, text),
+                format!(
+                    "field `{}` ({}) from {:?}: {}",
+                    field,
+                    std::any::type_name::<T>(),
+                    text,
+                    err
+                ),
+            )
+        })
+    }
+
+    /// Split `text` on `separator`, trimming whitespace from each item.
+    ///
+    /// Text which is empty or entirely whitespace produces no items.
+    pub fn split<'a>(text: &'a str, separator: &'a str) -> impl 'a + Iterator<Item = &'a str> {
+        let empty = text.trim().is_empty();
+        text.split(separator)
+            .map(str::trim)
+            .take_while(move |_| !empty)
+    }
+
+    /// Take the next integer for the field named `field`.
+    pub fn next_int<'a>(
+        input: &'a str,
+        ints: &mut impl Iterator<Item = &'a str>,
+        field: &str,
+    ) -> Result<&'a str, AocParseError> {
+        ints.next().ok_or_else(|| {
+            AocParseError::new(
+                input.chars().count() + 1,
+                format!("expected an integer for field `{}`", field),
+            )
+        })
+    }
+
+    /// Ensure that no integers remain.
+    pub fn no_more_ints<'a>(
+        input: &'a str,
+        ints: &mut impl Iterator<Item = &'a str>,
+    ) -> Result<(), AocParseError> {
+        match ints.next() {
+            None => Ok(()),
+            Some(extra) => Err(AocParseError::new(
+                column_of(input, extra),
+                format!("unexpected extra integer {:?}", extra),
+            )),
+        }
+    }
+
+    /// Choose the error to report when no enum variant matched.
+    ///
+    /// The variant which progressed furthest through the input is most likely to be the
+    /// one which was intended, so its error is reported.
+    pub fn no_variant_matched(errors: Vec<(&str, AocParseError)>) -> AocParseError {
+        match errors
+            .into_iter()
+            .enumerate()
+            .max_by_key(|(idx, (_, err))| (err.column, std::cmp::Reverse(*idx)))
+        {
+            Some((_, (variant, err))) => AocParseError::new(
+                err.column,
+                format!(
+                    "no variant matched; closest was `{}`: {}",
+                    variant, err.message
+                ),
+            ),
+            None => AocParseError::new(1, "type has no variants"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::__private::*;
+
+    #[test]
+    fn test_column_of_counts_chars() {
+        let input = "é=12";
+        assert_eq!(column_of(input, &input[3..]), 3);
+        assert_eq!(column_of(input, "unrelated"), 1);
+    }
+
+    #[test]
+    fn test_split_trims_and_handles_empty() {
+        assert_eq!(
+            split(" a, b ,c", ",").collect::<Vec<_>>(),
+            vec!["a", "b", "c"]
+        );
+        assert_eq!(split("  ", ",").count(), 0);
+    }
+
+    #[test]
+    fn test_closest_variant_reported() {
+        let err = no_variant_matched(vec![
+            ("A", AocParseError::new(1, "a")),
+            ("B", AocParseError::new(5, "b")),
+            ("C", AocParseError::new(5, "c")),
+        ]);
+        assert_eq!(err.column, 5);
+        assert_eq!(err.message, "no variant matched; closest was `B`: b");
+    }
+}
@@ -0,0 +1,409 @@
+use super::{Normalization, TEST_DATA_FILENAME};
+use std::{
+    convert::Infallible,
+    fmt::Display,
+    fs::File,
+    io::{prelude::*, BufReader},
+    path::Path,
+    str::FromStr,
+};
+
+/// Parse a value from a string slice, potentially borrowing from it.
+///
+/// This is like [`FromStr`], except that the parsed value may retain references into the input.
+/// It is used by [`parse_borrowed`] and related functions, which load an entire input once and
+/// yield records which borrow from it.
+///
+/// For types which implement `FromStr` and do not need to borrow, use the [`Owned`] adaptor.
+pub trait ParseBorrowed<'a>: Sized {
+    type Err;
+
+    fn parse_borrowed(s: &'a str) -> Result<Self, Self::Err>;
+}
+
+impl<'a> ParseBorrowed<'a> for &'a str {
+    type Err = Infallible;
+
+    fn parse_borrowed(s: &'a str) -> Result<Self, Self::Err> {
+        Ok(s)
+    }
+}
+
+/// This adaptor plugs any `FromStr` type into the borrowing parse functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Owned<T>(pub T);
+
+impl<'a, T: FromStr> ParseBorrowed<'a> for Owned<T> {
+    type Err = <T as FromStr>::Err;
+
+    fn parse_borrowed(s: &'a str) -> Result<Self, Self::Err> {
+        s.parse().map(Owned)
+    }
+}
+
+/// Parse each record in turn, printing a message to stderr and halting on the first failure.
+fn parse_records<'a, T, Filename>(
+    records: impl 'a + Iterator<Item = (usize, &'a str)>,
+    file_name: Filename,
+) -> impl 'a + Iterator<Item = T>
+where
+    T: 'a + ParseBorrowed<'a>,
+    <T as ParseBorrowed<'a>>::Err: Display,
+    Filename: 'a + Display,
+{
+    records
+        .map_while(move |(line, record)| match T::parse_borrowed(record) {
+            Ok(t) => Some(t),
+            Err(e) => {
+                eprintln!("{}:{}: {} for {:?}", file_name, line, e, record);
+                None
+            }
+        })
+        .fuse()
+}
+
+/// Iterate over the lines of `data`, with their 1-based line numbers.
+///
+/// Each line is normalized according to `normalization`.
+fn lines(data: &str, normalization: Normalization) -> impl '_ + Iterator<Item = (usize, &str)> {
+    data.split_inclusive('\n')
+        .enumerate()
+        .map(move |(idx, line)| (idx + 1, normalization.line(line, idx == 0)))
+}
+
+/// Iterate over the clusters of lines in `data` which are separated by blank lines,
+/// with the 1-based line number on which each cluster begins.
+///
+/// Each cluster includes its internal newlines, but not the newline which ends its last line.
+///
+/// Because clusters are slices of `data`, normalization can only be applied to the start of
+/// the first line and the end of the last line of each cluster. It also determines which lines
+/// are blank.
+fn clusters(data: &str, normalization: Normalization) -> impl '_ + Iterator<Item = (usize, &str)> {
+    let offset_of = move |slice: &str| slice.as_ptr() as usize - data.as_ptr() as usize;
+    let mut lines = lines(data, normalization).peekable();
+    std::iter::from_fn(move || {
+        while lines.next_if(|(_, line)| line.is_empty()).is_some() {}
+        let (line_number, first) = lines.next()?;
+        let start = offset_of(first);
+        let mut end = start + first.len();
+        while let Some((_, line)) = lines.next_if(|(_, line)| !line.is_empty()) {
+            end = offset_of(line) + line.len();
+        }
+        Some((line_number, &data[start..end]))
+    })
+}
+
+/// Read the entirety of `reader` into `buf`, replacing its previous contents.
+fn load(mut reader: impl Read, buf: &mut String) -> std::io::Result<&str> {
+    buf.clear();
+    reader.read_to_string(buf)?;
+    Ok(buf.as_str())
+}
+
+/// Parse the provided data into a stream of `T` which may borrow from it.
+///
+/// Each line is treated as a separate record. The input is normalized with
+/// [`Normalization::trimmed`]: leading and trailing whitespace is trimmed before
+/// each line is handed to the parser.
+///
+/// If any record cannot be parsed, this prints the parse error on stderr and stops iteration.
+///
+/// See also [`parse_borrowed`] for equivalent functionality for input files.
+pub fn parse_borrowed_str<'a, T>(data: &'a str) -> impl 'a + Iterator<Item = T>
+where
+    T: 'a + ParseBorrowed<'a>,
+    <T as ParseBorrowed<'a>>::Err: Display,
+{
+    parse_records(lines(data, Normalization::trimmed()), TEST_DATA_FILENAME)
+}
+
+/// Load the contents of the provided reader into `buf`, then parse it into a stream of `T`
+/// which may borrow from it.
+///
+/// Often [`parse_borrowed`] or [`parse_borrowed_str`] are more ergonomic.
+///
+/// Each line is treated as a separate record. The input is normalized with
+/// [`Normalization::trimmed`]: leading and trailing whitespace is trimmed before
+/// each line is handed to the parser.
+///
+/// If any record cannot be parsed, this prints the parse error on stderr and stops iteration.
+///
+/// The file name can technically be anything which is `'a + Display`, but it's used within
+/// error messages as the file name, so it should be reasonably interprable as such.
+pub fn parse_borrowed_reader<'a, T, Reader, Filename>(
+    reader: Reader,
+    buf: &'a mut String,
+    file_name: Filename,
+) -> std::io::Result<impl 'a + Iterator<Item = T>>
+where
+    T: 'a + ParseBorrowed<'a>,
+    <T as ParseBorrowed<'a>>::Err: Display,
+    Reader: Read,
+    Filename: 'a + Display,
+{
+    parse_borrowed_reader_with(reader, buf, file_name, Normalization::trimmed())
+}
+
+/// Load the contents of the provided reader into `buf`, then parse it into a stream of `T`
+/// which may borrow from it, normalizing the input according to the provided policy.
+///
+/// Each line is treated as a separate record.
+///
+/// If any record cannot be parsed, this prints the parse error on stderr and stops iteration.
+pub fn parse_borrowed_reader_with<'a, T, Reader, Filename>(
+    reader: Reader,
+    buf: &'a mut String,
+    file_name: Filename,
+    normalization: Normalization,
+) -> std::io::Result<impl 'a + Iterator<Item = T>>
+where
+    T: 'a + ParseBorrowed<'a>,
+    <T as ParseBorrowed<'a>>::Err: Display,
+    Reader: Read,
+    Filename: 'a + Display,
+{
+    let data = load(reader, buf)?;
+    Ok(parse_records(lines(data, normalization), file_name))
+}
+
+/// Load the file at the specified path into `buf`, then parse it into a stream of `T`
+/// which may borrow from it.
+///
+/// The whole file is read at once, so each record avoids a separate allocation.
+/// `buf` must outlive the records, so it is supplied by the caller.
+///
+/// Each line is treated as a separate record. The input is normalized with
+/// [`Normalization::trimmed`]: leading and trailing whitespace is trimmed before
+/// each line is handed to the parser.
+///
+/// If any record cannot be parsed, this prints the parse error on stderr and stops iteration.
+///
+/// See also [`parse_borrowed_str`] for equivalent functionality for strings, useful for test data.
+///
+/// ## Example
+///
+/// ```no_run
+/// # use aoclib::input::parse_borrowed;
+/// # use std::path::Path;
+/// let mut buf = String::new();
+/// for word in parse_borrowed::<&str>(Path::new("input.txt"), &mut buf)? {
+///     println!("{}", word);
+/// }
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn parse_borrowed<'a, T>(
+    path: &Path,
+    buf: &'a mut String,
+) -> std::io::Result<impl 'a + Iterator<Item = T>>
+where
+    T: 'a + ParseBorrowed<'a>,
+    <T as ParseBorrowed<'a>>::Err: Display,
+{
+    let file = File::open(path)?;
+    parse_borrowed_reader(
+        BufReader::new(file),
+        buf,
+        path.file_name()
+            .expect("File::open() didn't early return before now; qed")
+            .to_string_lossy()
+            .into_owned(),
+    )
+}
+
+/// Normalize the provided data into `buf`, then parse it into a stream of `T` which may borrow
+/// from it.
+///
+/// Lines are batched into clusters separated by blank lines. Each cluster, including its
+/// internal newlines but excluding the newline which ends it, is parsed into a `T` instance.
+///
+/// As whitespace is potentially significant, the input is normalized with the default
+/// [`Normalization`], which does not trim whitespace. The whole input is normalized into `buf`
+/// before parsing, so normalization applies to the interior of each cluster as well as its edges.
+///
+/// If any record cannot be parsed, this prints the parse error on stderr and stops iteration.
+///
+/// See also [`parse_newline_sep_borrowed`] for equivalent functionality for input files.
+pub fn parse_newline_sep_borrowed_str<'a, T>(
+    data: &str,
+    buf: &'a mut String,
+) -> impl 'a + Iterator<Item = T>
+where
+    T: 'a + ParseBorrowed<'a>,
+    <T as ParseBorrowed<'a>>::Err: Display,
+{
+    *buf = Normalization::default().apply(data);
+    // the buffer is already normalized; normalizing it again could remove too much
+    parse_records(clusters(buf, Normalization::raw()), TEST_DATA_FILENAME)
+}
+
+/// Load the contents of the provided reader into `buf`, then parse it into a stream of `T`
+/// which may borrow from it.
+///
+/// Often [`parse_newline_sep_borrowed`] or [`parse_newline_sep_borrowed_str`] are more ergonomic.
+///
+/// Lines are batched into clusters separated by blank lines. Each cluster, including its
+/// internal newlines but excluding the newline which ends it, is parsed into a `T` instance.
+///
+/// As whitespace is potentially significant, the input is normalized with the default
+/// [`Normalization`], which does not trim whitespace.
+///
+/// If any record cannot be parsed, this prints the parse error on stderr and stops iteration.
+pub fn parse_newline_sep_borrowed_reader<'a, T, Reader, Filename>(
+    reader: Reader,
+    buf: &'a mut String,
+    file_name: Filename,
+) -> std::io::Result<impl 'a + Iterator<Item = T>>
+where
+    T: 'a + ParseBorrowed<'a>,
+    <T as ParseBorrowed<'a>>::Err: Display,
+    Reader: Read,
+    Filename: 'a + Display,
+{
+    parse_newline_sep_borrowed_reader_with(reader, buf, file_name, Normalization::default())
+}
+
+/// Load the contents of the provided reader into `buf`, then parse it into a stream of `T`
+/// which may borrow from it, normalizing the input according to the provided policy.
+///
+/// Lines are batched into clusters separated by lines which are blank after normalization.
+/// Each cluster, including its internal newlines but excluding the newline which ends it,
+/// is parsed into a `T` instance.
+///
+/// The entire buffer is normalized once when it is loaded, so normalization applies to the
+/// interior of each cluster as well as its edges.
+///
+/// If any record cannot be parsed, this prints the parse error on stderr and stops iteration.
+pub fn parse_newline_sep_borrowed_reader_with<'a, T, Reader, Filename>(
+    reader: Reader,
+    buf: &'a mut String,
+    file_name: Filename,
+    normalization: Normalization,
+) -> std::io::Result<impl 'a + Iterator<Item = T>>
+where
+    T: 'a + ParseBorrowed<'a>,
+    <T as ParseBorrowed<'a>>::Err: Display,
+    Reader: Read,
+    Filename: 'a + Display,
+{
+    *buf = normalization.apply(load(reader, buf)?);
+    // the buffer is already normalized; normalizing it again could remove too much
+    Ok(parse_records(
+        clusters(buf, Normalization::raw()),
+        file_name,
+    ))
+}
+
+/// Load the file at the specified path into `buf`, then parse it into a stream of `T`
+/// which may borrow from it.
+///
+/// Lines are batched into clusters separated by blank lines. Each cluster, including its
+/// internal newlines but excluding the newline which ends it, is parsed into a `T` instance.
+///
+/// As whitespace is potentially significant, the input is normalized with the default
+/// [`Normalization`], which does not trim whitespace.
+///
+/// If any record cannot be parsed, this prints the parse error on stderr and stops iteration.
+///
+/// See also [`parse_newline_sep_borrowed_str`] for equivalent functionality for strings, useful for test data.
+pub fn parse_newline_sep_borrowed<'a, T>(
+    path: &Path,
+    buf: &'a mut String,
+) -> std::io::Result<impl 'a + Iterator<Item = T>>
+where
+    T: 'a + ParseBorrowed<'a>,
+    <T as ParseBorrowed<'a>>::Err: Display,
+{
+    let file = File::open(path)?;
+    parse_newline_sep_borrowed_reader(
+        BufReader::new(file),
+        buf,
+        path.file_name()
+            .expect("File::open() didn't early return before now; qed")
+            .to_string_lossy()
+            .into_owned(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Edge<'a> {
+        from: &'a str,
+        to: &'a str,
+    }
+
+    impl<'a> ParseBorrowed<'a> for Edge<'a> {
+        type Err = &'static str;
+
+        fn parse_borrowed(s: &'a str) -> Result<Self, Self::Err> {
+            let (from, to) = s.split_once('-').ok_or("missing hyphen")?;
+            Ok(Edge { from, to })
+        }
+    }
+
+    #[test]
+    fn test_records_borrow_from_input() {
+        let data = "start-A\n  A-end  \n";
+        let edges: Vec<Edge> = parse_borrowed_str(data).collect();
+        assert_eq!(
+            edges,
+            vec![
+                Edge {
+                    from: "start",
+                    to: "A"
+                },
+                Edge {
+                    from: "A",
+                    to: "end"
+                },
+            ]
+        );
+        let data_range = data.as_bytes().as_ptr_range();
+        assert!(data_range.contains(&edges[1].from.as_ptr()));
+    }
+
+    #[test]
+    fn test_halts_on_error() {
+        let edges: Vec<Edge> = parse_borrowed_str("a-b\nc\nd-e").collect();
+        assert_eq!(edges.len(), 1);
+    }
+
+    #[test]
+    fn test_owned_adaptor() {
+        let mut buf = String::new();
+        let values: Vec<u32> = parse_borrowed_reader(Cursor::new("1\n2\n3\n"), &mut buf, "test")
+            .unwrap()
+            .map(|Owned(value)| value)
+            .collect();
+        assert_eq!(values, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_clusters() {
+        let data = "\na\nb\n\n\nc\r\n\r\nd\r\ne\n";
+        let mut buf = String::new();
+        let clusters: Vec<&str> = parse_newline_sep_borrowed_str(data, &mut buf).collect();
+        assert_eq!(clusters, vec!["a\nb", "c", "d\ne"]);
+        let lines: Vec<usize> = super::clusters(data, Normalization::default())
+            .map(|(line, _)| line)
+            .collect();
+        assert_eq!(lines, vec![2, 6, 8]);
+    }
+
+    #[test]
+    fn test_reader_normalizes_cluster_interior() {
+        let mut buf = String::new();
+        let clusters: Vec<&str> = parse_newline_sep_borrowed_reader(
+            Cursor::new("\u{feff}a\r\nb\r\n\r\nc\r\n"),
+            &mut buf,
+            "test",
+        )
+        .unwrap()
+        .collect();
+        assert_eq!(clusters, vec!["a\nb", "c"]);
+    }
+}
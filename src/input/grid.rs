@@ -0,0 +1,313 @@
+use super::{
+    tuple::{parse_element, ElementError},
+    Normalization, NormalizedLines,
+};
+use std::{fmt, io::BufRead, str::FromStr};
+
+/// A blank line separated two rows of a grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("line {line}: blank row within grid")]
+pub struct BlankRow {
+    /// 1-based line number of the blank line.
+    pub line: usize,
+}
+
+impl From<BlankRow> for std::io::Error {
+    fn from(err: BlankRow) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, err)
+    }
+}
+
+/// Read the lines of `reader` as rows of a grid.
+///
+/// Each normalized line is split into cells by `parse_row`. Lines which produce no cells are
+/// blank. Blank lines before the first row and after the last row are skipped; a blank line
+/// between two rows is an error, as skipping it would shift every later row.
+/// Rows are returned in input order, so the first row is the top of the grid.
+///
+/// This is the machinery shared by [`CharGrid`] and `Map::try_from`.
+pub(crate) fn read_rows<Reader, T, E>(
+    reader: Reader,
+    normalization: Normalization,
+    mut parse_row: impl FnMut(&str) -> Result<Vec<T>, E>,
+) -> Result<Vec<Vec<T>>, E>
+where
+    Reader: BufRead,
+    E: From<std::io::Error> + From<BlankRow>,
+{
+    let mut rows = Vec::new();
+    let mut blank = None;
+    let mut lines = NormalizedLines::new(reader, normalization);
+    while let Some((line_number, line)) = lines.try_next_line()? {
+        let row = parse_row(line)?;
+        if row.is_empty() {
+            if !rows.is_empty() && blank.is_none() {
+                blank = Some(line_number);
+            }
+            continue;
+        }
+        if let Some(line) = blank {
+            return Err(BlankRow { line }.into());
+        }
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+/// A block of text viewed as a rectangular grid of characters.
+///
+/// This is useful for inputs which are laid out in columns rather than lines, such as stacks
+/// of crates drawn vertically, or worksheets in which each problem occupies a column.
+///
+/// Rows are read from the input in order; blank lines before and after the grid are skipped,
+/// but a blank line between rows is an error. Rows shorter than the
+/// longest row are padded on the right with spaces, so ragged input (for example, from an
+/// editor which strips trailing whitespace) still lines up by column.
+///
+/// ## Example
+///
+/// ```
+/// # use aoclib::input::CharGrid;
+/// let grid = CharGrid::new("
+/// 123 328
+///  45 64
+///   6 98
+/// ");
+///
+/// // each column is read top to bottom; the blank separating column is skipped
+/// let columns: Vec<u32> = grid.parse_columns().unwrap();
+/// assert_eq!(columns, vec![1, 24, 356, 369, 248, 8]);
+///
+/// let blocks = grid.column_blocks();
+/// assert_eq!(blocks.len(), 2);
+/// assert_eq!(blocks[1].to_string(), "328\n64 \n98 ");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct CharGrid {
+    rows: Vec<Vec<char>>,
+    width: usize,
+}
+
+impl CharGrid {
+    /// Read a grid from a string.
+    ///
+    /// The input is normalized with the default [`Normalization`]: leading whitespace is
+    /// significant, so it is not trimmed.
+    ///
+    /// ## Panics
+    ///
+    /// If a blank line separates two rows. Use [`str::parse`] to handle that as an error.
+    pub fn new(text: &str) -> CharGrid {
+        match text.parse() {
+            Ok(grid) => grid,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Read a grid from a reader, normalizing the input according to the provided policy.
+    pub fn from_reader_with<Reader: BufRead>(
+        reader: Reader,
+        normalization: Normalization,
+    ) -> std::io::Result<CharGrid> {
+        let rows = read_rows(reader, normalization, |line| {
+            Ok::<_, std::io::Error>(line.chars().collect())
+        })?;
+        Ok(CharGrid::from_rows(rows))
+    }
+
+    /// Construct a grid from rows of characters, padding short rows with spaces.
+    fn from_rows(mut rows: Vec<Vec<char>>) -> CharGrid {
+        let width = rows.iter().map(Vec::len).max().unwrap_or_default();
+        for row in rows.iter_mut() {
+            row.resize(width, ' ');
+        }
+        CharGrid { rows, width }
+    }
+
+    /// Number of columns in the grid.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Number of rows in the grid.
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Get the character at a particular row and column, if it is within the grid.
+    ///
+    /// Row 0 is the top of the grid; column 0 is the left.
+    pub fn get(&self, row: usize, column: usize) -> Option<char> {
+        self.rows.get(row)?.get(column).copied()
+    }
+
+    /// Iterate over the rows of the grid, top to bottom.
+    pub fn rows(&self) -> impl '_ + Iterator<Item = String> {
+        self.rows.iter().map(|row| row.iter().collect())
+    }
+
+    /// Iterate over the columns of the grid, left to right.
+    ///
+    /// Each column is read top to bottom. Padding is not removed.
+    pub fn columns(&self) -> impl '_ + Iterator<Item = String> {
+        (0..self.width).map(move |column| self.column(column))
+    }
+
+    fn column(&self, column: usize) -> String {
+        self.rows.iter().map(|row| row[column]).collect()
+    }
+
+    fn column_is_blank(&self, column: usize) -> bool {
+        self.rows.iter().all(|row| row[column].is_whitespace())
+    }
+
+    /// Split the grid into blocks of columns separated by columns which are entirely blank.
+    ///
+    /// The separating columns are not included in any block. Each block has the full height of the grid.
+    pub fn column_blocks(&self) -> Vec<CharGrid> {
+        let mut blocks = Vec::new();
+        let mut start = None;
+        for column in 0..=self.width {
+            let blank = column == self.width || self.column_is_blank(column);
+            match (start, blank) {
+                (None, false) => start = Some(column),
+                (Some(low), true) => {
+                    blocks.push(self.column_range(low, column));
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        blocks
+    }
+
+    fn column_range(&self, low: usize, high: usize) -> CharGrid {
+        CharGrid {
+            rows: self
+                .rows
+                .iter()
+                .map(|row| row[low..high].to_vec())
+                .collect(),
+            width: high - low,
+        }
+    }
+
+    /// Swap the rows and columns of this grid.
+    ///
+    /// Row `n` of the output is column `n` of the input, read top to bottom.
+    pub fn transpose(&self) -> CharGrid {
+        CharGrid {
+            rows: (0..self.width)
+                .map(|column| self.rows.iter().map(|row| row[column]).collect())
+                .collect(),
+            width: self.rows.len(),
+        }
+    }
+
+    /// Parse each column of the grid, left to right.
+    ///
+    /// Each column is trimmed before parsing. Columns which are entirely blank are skipped.
+    /// On failure, the error's `index` is the index of the column within the grid.
+    pub fn parse_columns<T>(&self) -> Result<Vec<T>, ElementError>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: fmt::Display,
+    {
+        self.columns()
+            .enumerate()
+            .filter(|(_, column)| !column.trim().is_empty())
+            .map(|(idx, column)| parse_element(idx, column.trim()))
+            .collect()
+    }
+
+    /// Parse each block of columns, as produced by [`column_blocks`][Self::column_blocks].
+    ///
+    /// Each block is rendered as text, with its rows joined by `\n`, before parsing.
+    /// On failure, the error's `index` is the index of the block.
+    pub fn parse_column_blocks<T>(&self) -> Result<Vec<T>, ElementError>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: fmt::Display,
+    {
+        self.column_blocks()
+            .iter()
+            .enumerate()
+            .map(|(idx, block)| parse_element(idx, &block.to_string()))
+            .collect()
+    }
+}
+
+impl FromStr for CharGrid {
+    type Err = BlankRow;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CharGrid::from_reader_with(s.as_bytes(), Normalization::default()).map_err(|err| {
+            *err.into_inner()
+                .and_then(|inner| inner.downcast().ok())
+                .expect("reading from a byte slice can only fail on a blank row")
+        })
+    }
+}
+
+impl fmt::Display for CharGrid {
+    /// Rows are joined by `\n`; there is no trailing newline.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, row) in self.rows.iter().enumerate() {
+            if idx != 0 {
+                writeln!(f)?;
+            }
+            for ch in row {
+                write!(f, "{}", ch)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CRATES: &str = "    [D]    \n[N] [C]\n[Z] [M] [P]\n 1   2   3 \n";
+
+    #[test]
+    fn test_ragged_rows_are_padded() {
+        let grid = CharGrid::new(CRATES);
+        assert_eq!(grid.width(), 11);
+        assert_eq!(grid.height(), 4);
+        assert_eq!(grid.get(1, 10), Some(' '));
+    }
+
+    #[test]
+    fn test_stacks_from_columns() {
+        let grid = CharGrid::new(CRATES);
+        let stacks: Vec<String> = grid
+            .columns()
+            .skip(1)
+            .step_by(4)
+            .map(|column| column.trim().to_owned())
+            .collect();
+        assert_eq!(stacks, vec!["NZ1", "DCM2", "P3"]);
+    }
+
+    #[test]
+    fn test_interior_blank_row() {
+        assert_eq!("\nab\ncd\n\n".parse::<CharGrid>().unwrap().height(), 2);
+        assert_eq!("ab\n\ncd\n".parse::<CharGrid>(), Err(BlankRow { line: 2 }));
+    }
+
+    #[test]
+    fn test_transpose() {
+        let grid = CharGrid::new("ab\ncd\nef");
+        assert_eq!(grid.transpose().to_string(), "ace\nbdf");
+        assert_eq!(grid.transpose().transpose(), grid);
+    }
+
+    #[test]
+    fn test_parse_column_error_index() {
+        let grid = CharGrid::new("1 x\n2 3");
+        let err = grid.parse_columns::<u32>().unwrap_err();
+        assert_eq!(err.index, 2);
+        assert_eq!(err.text, "x3");
+    }
+}
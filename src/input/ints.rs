@@ -0,0 +1,202 @@
+use super::tuple::{parse_element, ElementError, ParseTuple};
+use std::{convert::TryInto, fmt::Display, ops::Deref, str::FromStr};
+
+/// Iterate over the substrings of `s` which represent signed integers.
+///
+/// A `-` is treated as a sign when it immediately precedes a digit, unless it also immediately
+/// follows a digit. This means that ranges such as `1-3` produce `1` and `3`, not `1` and `-3`.
+///
+/// ```
+/// # use aoclib::input::int_strs;
+/// let line = "Sensor at x=2, y=-18: range 1-3";
+/// assert_eq!(int_strs(line).collect::<Vec<_>>(), vec!["2", "-18", "1", "3"]);
+/// ```
+pub fn int_strs(s: &str) -> impl '_ + Iterator<Item = &str> {
+    let bytes = s.as_bytes();
+    let mut idx = 0;
+    std::iter::from_fn(move || {
+        while idx < bytes.len() {
+            let start = idx;
+            let is_sign = bytes[idx] == b'-'
+                && bytes.get(idx + 1).is_some_and(u8::is_ascii_digit)
+                && (idx == 0 || !bytes[idx - 1].is_ascii_digit());
+            if is_sign {
+                idx += 1;
+            }
+            if bytes[idx].is_ascii_digit() {
+                while idx < bytes.len() && bytes[idx].is_ascii_digit() {
+                    idx += 1;
+                }
+                return Some(&s[start..idx]);
+            }
+            idx += 1;
+        }
+        None
+    })
+}
+
+/// Extract every signed integer from `s`.
+///
+/// Everything which is not part of an integer is ignored.
+///
+/// ```
+/// # use aoclib::input::ints;
+/// let line = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15";
+/// assert_eq!(ints::<i32>(line).unwrap(), vec![2, 18, -2, 15]);
+/// ```
+pub fn ints<T>(s: &str) -> Result<Vec<T>, IntsError>
+where
+    T: FromStr,
+    <T as FromStr>::Err: Display,
+{
+    int_strs(s)
+        .enumerate()
+        .map(|(index, text)| parse_element(index, text).map_err(Into::into))
+        .collect()
+}
+
+/// Extract exactly `N` signed integers from `s`.
+///
+/// Everything which is not part of an integer is ignored. It is an error if `s` contains
+/// more or fewer than `N` integers.
+///
+/// ```
+/// # use aoclib::input::ints_array;
+/// let line = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15";
+/// let [sx, sy, bx, by] = ints_array::<i32, 4>(line).unwrap();
+/// assert_eq!((sx, sy, bx, by), (2, 18, -2, 15));
+/// ```
+pub fn ints_array<T, const N: usize>(s: &str) -> Result<[T; N], IntsError>
+where
+    T: FromStr,
+    <T as FromStr>::Err: Display,
+{
+    let ints = ints(s)?;
+    let found = ints.len();
+    ints.try_into()
+        .map_err(|_| IntsError::WrongCount { expected: N, found })
+}
+
+/// Extract a tuple of signed integers from `s`.
+///
+/// Everything which is not part of an integer is ignored. It is an error if `s` contains
+/// more or fewer integers than the tuple has elements. The element types may differ.
+///
+/// ```
+/// # use aoclib::input::ints_tuple;
+/// let line = "move 3 from 1 to 2";
+/// let (count, from, to): (u32, usize, usize) = ints_tuple(line).unwrap();
+/// assert_eq!((count, from, to), (3, 1, 2));
+/// ```
+pub fn ints_tuple<T: ParseTuple>(s: &str) -> Result<T, IntsError> {
+    let items: Vec<_> = int_strs(s).collect();
+    if items.len() != T::ARITY {
+        return Err(IntsError::WrongCount {
+            expected: T::ARITY,
+            found: items.len(),
+        });
+    }
+    T::parse_tuple(&items).map_err(Into::into)
+}
+
+/// An error extracting integers from a string.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum IntsError {
+    #[error("expected {expected} integers but found {found}")]
+    WrongCount { expected: usize, found: usize },
+    #[error(transparent)]
+    Element(#[from] ElementError),
+}
+
+/// This adaptor plugs into any of the parse functions, extracting every signed integer from each line.
+///
+/// Everything which is not part of an integer is ignored. See [`ints`] for details.
+pub struct Ints<T>(Vec<T>);
+
+impl<T> FromStr for Ints<T>
+where
+    T: FromStr,
+    <T as FromStr>::Err: Display,
+{
+    type Err = IntsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ints(s).map(Ints)
+    }
+}
+
+impl<T> IntoIterator for Ints<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<T> From<Ints<T>> for Vec<T> {
+    fn from(ints: Ints<T>) -> Self {
+        ints.0
+    }
+}
+
+impl<T> Deref for Ints<T> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::parse_str;
+
+    #[test]
+    fn test_ranges_are_not_negative() {
+        assert_eq!(ints::<i32>("1-3 a: abcde").unwrap(), vec![1, 3]);
+        assert_eq!(ints::<i32>("-1--3").unwrap(), vec![-1, -3]);
+    }
+
+    #[test]
+    fn test_lone_hyphen_ignored() {
+        assert_eq!(ints::<i32>("a - b -c 4-").unwrap(), vec![4]);
+    }
+
+    #[test]
+    fn test_array_wrong_count() {
+        assert_eq!(
+            ints_array::<i32, 3>("1 2"),
+            Err(IntsError::WrongCount {
+                expected: 3,
+                found: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_tuple_reports_failing_element() {
+        let err = ints_tuple::<(u8, u8)>("1 and 300").unwrap_err();
+        match err {
+            IntsError::Element(ElementError { index, text, .. }) => {
+                assert_eq!(index, 1);
+                assert_eq!(text, "300");
+            }
+            other => panic!("unexpected error: {}", other),
+        }
+    }
+
+    #[test]
+    fn test_adaptor_plugs_into_parse() {
+        const EXAMPLE: &str = "
+Sensor at x=2, y=18: closest beacon is at x=-2, y=15
+Sensor at x=9, y=16: closest beacon is at x=10, y=16
+";
+        let records: Vec<Vec<i64>> = parse_str::<Ints<i64>>(EXAMPLE.trim())
+            .unwrap()
+            .map(Into::into)
+            .collect();
+        assert_eq!(records, vec![vec![2, 18, -2, 15], vec![9, 16, 10, 16]]);
+    }
+}
@@ -1,3 +1,36 @@
+mod aoc_parse;
+mod borrowed;
+mod grid;
+mod ints;
+mod normalize;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod record;
+mod scan;
+mod tuple;
+
+pub use aoc_parse::AocParseError;
+pub use borrowed::{
+    parse_borrowed, parse_borrowed_reader, parse_borrowed_reader_with, parse_borrowed_str,
+    parse_newline_sep_borrowed, parse_newline_sep_borrowed_reader,
+    parse_newline_sep_borrowed_reader_with, parse_newline_sep_borrowed_str, Owned, ParseBorrowed,
+};
+pub use grid::{BlankRow, CharGrid};
+pub use ints::{int_strs, ints, ints_array, ints_tuple, Ints, IntsError};
+pub use normalize::{Normalization, Trim};
+#[cfg(feature = "rayon")]
+pub use parallel::{par_parse, par_parse_reader_with, par_parse_str};
+pub use record::{FieldError, Record, RecordError};
+pub use scan::{Capture, ScanError, ScanPattern, TemplateError};
+pub use tuple::{ElementError, ParseTuple};
+
+#[cfg(feature = "derive")]
+pub use aoclib_derive::AocParse;
+
+#[doc(hidden)]
+pub use aoc_parse::__private as __aoc_parse;
+pub(crate) use grid::read_rows;
+pub(crate) use normalize::NormalizedLines;
 use std::{
     fmt::Display,
     fs::File,
@@ -11,8 +44,9 @@ const TEST_DATA_FILENAME: &str = "TEST DATA";
 
 /// Parse the file at the specified path into a stream of `T`.
 ///
-/// Each line is treated as a separate record. Leading and trailing spaces
-/// are trimmed before being handed to the parser.
+/// Each line is treated as a separate record. The input is normalized with
+/// [`Normalization::trimmed`]: leading and trailing whitespace is trimmed before
+/// each line is handed to the parser.
 ///
 /// If any record cannot be parsed, this prints the parse error on stderr and stops iteration.
 ///
@@ -34,8 +68,9 @@ where
 
 /// Parse the provided data into a stream of `T`.
 ///
-/// Each line is treated as a separate record. Leading and trailing spaces
-/// are trimmed before being handed to the parser.
+/// Each line is treated as a separate record. The input is normalized with
+/// [`Normalization::trimmed`]: leading and trailing whitespace is trimmed before
+/// each line is handed to the parser.
 ///
 /// If any record cannot be parsed, this prints the parse error on stderr and stops iteration.
 ///
@@ -52,16 +87,37 @@ where
 ///
 /// Often [`parse`] or [`parse_str`] are more ergonomic.
 ///
-/// Each line is treated as a separate record. Leading and trailing spaces
-/// are trimmed before being handed to the parser.
+/// Each line is treated as a separate record. The input is normalized with
+/// [`Normalization::trimmed`]: leading and trailing whitespace is trimmed before
+/// each line is handed to the parser.
 ///
 /// If any record cannot be parsed, this prints the parse error on stderr and stops iteration.
 ///
 /// The file name can technically be anything which is `'a + Display`, but it's used within
 /// error messages as the file name, so it should be reasonably interprable as such.
 pub fn parse_reader<'a, T, Reader, Filename>(
-    mut reader: Reader,
+    reader: Reader,
+    file_name: Filename,
+) -> std::io::Result<impl 'a + Iterator<Item = T>>
+where
+    T: FromStr,
+    <T as FromStr>::Err: std::fmt::Display,
+    Reader: 'a + BufRead,
+    Filename: 'a + std::fmt::Display,
+{
+    parse_reader_with(reader, file_name, Normalization::trimmed())
+}
+
+/// Parse the contents of the provided reader into a stream of `T`, normalizing the input
+/// according to the provided policy.
+///
+/// Each line is treated as a separate record.
+///
+/// If any record cannot be parsed, this prints the parse error on stderr and stops iteration.
+pub fn parse_reader_with<'a, T, Reader, Filename>(
+    reader: Reader,
     file_name: Filename,
+    normalization: Normalization,
 ) -> std::io::Result<impl 'a + Iterator<Item = T>>
 where
     T: FromStr,
@@ -69,22 +125,16 @@ where
     Reader: 'a + BufRead,
     Filename: 'a + std::fmt::Display,
 {
-    let mut buf = String::new();
-    let mut line: usize = 0;
+    let mut lines = NormalizedLines::new(reader, normalization);
     Ok(std::iter::from_fn(move || {
-        buf.clear();
-        reader.read_line(&mut buf).ok().and_then(|_| {
-            line += 1;
-            (!buf.is_empty())
-                .then(|| match T::from_str(buf.trim()) {
-                    Ok(t) => Some(t),
-                    Err(e) => {
-                        eprintln!("{}:{}: {} for {:?}", file_name, line, e, buf.trim());
-                        None
-                    }
-                })
-                .flatten()
-        })
+        let (line, record) = lines.next_line()?;
+        match T::from_str(record) {
+            Ok(t) => Some(t),
+            Err(e) => {
+                eprintln!("{}:{}: {} for {:?}", file_name, line, e, record);
+                None
+            }
+        }
     })
     .fuse())
 }
@@ -92,10 +142,11 @@ where
 /// Parse the file at the specified path into a stream of `T`.
 ///
 /// Lines are batched into clusters separated by blank lines. Once a cluster has been
-/// collected, it (and internal newlines) are parsed into a `T` instance.
+/// collected, it (and internal newlines) are parsed into a `T` instance. The cluster
+/// does not include a trailing newline.
 ///
-/// As whitespace is potentially significant, it is not adjusted in any way before being
-/// handed to the parser.
+/// As whitespace is potentially significant, the input is normalized with the default
+/// [`Normalization`], which does not trim whitespace.
 ///
 /// If any record cannot be parsed, this prints the parse error on stderr and stops iteration.
 ///
@@ -118,10 +169,11 @@ where
 /// Parse the provided data into a stream of `T`.
 ///
 /// Lines are batched into clusters separated by blank lines. Once a cluster has been
-/// collected, it (and internal newlines) are parsed into a `T` instance.
+/// collected, it (and internal newlines) are parsed into a `T` instance. The cluster
+/// does not include a trailing newline.
 ///
-/// As whitespace is potentially significant, it is not adjusted in any way before being
-/// handed to the parser.
+/// As whitespace is potentially significant, the input is normalized with the default
+/// [`Normalization`], which does not trim whitespace.
 ///
 /// If any record cannot be parsed, this prints the parse error on stderr and stops iteration.
 ///
@@ -134,43 +186,24 @@ where
     parse_newline_sep_reader(Cursor::new(data), TEST_DATA_FILENAME)
 }
 
-fn is_new_field(buf: &str) -> bool {
-    let patterns = ["\n\n", "\n\r\n"];
-    patterns.iter().any(|pat| {
-        buf.as_bytes()
-            .iter()
-            .rev()
-            .zip(pat.as_bytes().iter())
-            .all(|(b, p)| b == p)
-    })
-}
-
-fn get_next_item<T>(
-    buf: &mut String,
-    line: &mut usize,
-    reader: &mut impl BufRead,
+fn get_next_item<T, Reader>(
+    cluster: &mut String,
+    lines: &mut NormalizedLines<Reader>,
     file_name: &impl Display,
 ) -> Option<T>
 where
     T: FromStr,
     <T as FromStr>::Err: Display,
+    Reader: BufRead,
 {
-    buf.clear();
-    while buf.is_empty() || !is_new_field(buf) {
-        *line += 1;
-        if reader.read_line(buf).ok()? == 0 {
-            break;
+    let line = lines.next_cluster(cluster)?;
+    match T::from_str(cluster) {
+        Ok(t) => Some(t),
+        Err(e) => {
+            eprintln!("{}:{}: {} for {:?}", file_name, line, e, cluster);
+            None
         }
     }
-    (!buf.is_empty())
-        .then(|| match T::from_str(buf) {
-            Ok(t) => Some(t),
-            Err(e) => {
-                eprintln!("{}:{}: {} for {:?}", file_name, *line - 1, e, buf);
-                None
-            }
-        })
-        .flatten()
 }
 
 /// Parse the contents of the provided reader into a stream of `T`.
@@ -178,14 +211,15 @@ where
 /// Often [`parse_newline_sep`] or [`parse_newline_sep_str`] are more ergonomic.
 ///
 /// Lines are batched into clusters separated by blank lines. Once a cluster has been
-/// collected, it (and internal newlines) are parsed into a `T` instance.
+/// collected, it (and internal newlines) are parsed into a `T` instance. The cluster
+/// does not include a trailing newline.
 ///
-/// As whitespace is potentially significant, it is not adjusted in any way before being
-/// handed to the parser.
+/// As whitespace is potentially significant, the input is normalized with the default
+/// [`Normalization`], which does not trim whitespace.
 ///
 /// If any record cannot be parsed, this prints the parse error on stderr and stops iteration.
 pub fn parse_newline_sep_reader<'a, T, Reader, Filename>(
-    mut reader: Reader,
+    reader: Reader,
     file_name: Filename,
 ) -> std::io::Result<impl 'a + Iterator<Item = T>>
 where
@@ -194,13 +228,32 @@ where
     Reader: 'a + BufRead,
     Filename: 'a + std::fmt::Display,
 {
-    let mut buf = String::new();
-    let mut line: usize = 0;
+    parse_newline_sep_reader_with(reader, file_name, Normalization::default())
+}
 
-    Ok(
-        std::iter::from_fn(move || get_next_item(&mut buf, &mut line, &mut reader, &file_name))
-            .fuse(),
-    )
+/// Parse the contents of the provided reader into a stream of `T`, normalizing the input
+/// according to the provided policy.
+///
+/// Lines are batched into clusters separated by lines which are blank after normalization.
+/// Once a cluster has been collected, it (and internal newlines) are parsed into a `T` instance.
+/// The cluster does not include a trailing newline.
+///
+/// If any record cannot be parsed, this prints the parse error on stderr and stops iteration.
+pub fn parse_newline_sep_reader_with<'a, T, Reader, Filename>(
+    reader: Reader,
+    file_name: Filename,
+    normalization: Normalization,
+) -> std::io::Result<impl 'a + Iterator<Item = T>>
+where
+    T: FromStr,
+    <T as FromStr>::Err: std::fmt::Display,
+    Reader: 'a + BufRead,
+    Filename: 'a + std::fmt::Display,
+{
+    let mut cluster = String::new();
+    let mut lines = NormalizedLines::new(reader, normalization);
+
+    Ok(std::iter::from_fn(move || get_next_item(&mut cluster, &mut lines, &file_name)).fuse())
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -216,14 +269,40 @@ pub enum TwoPhaseError {
 /// Often [`parse_two_phase`] or [`parse_two_phase_str`] are more ergonomic.
 ///
 /// Lines are batched into clusters separated by blank lines. Once a cluster has been collected,
-/// it (and internal newlines) are parsed into an instance of the appropriate type.
+/// it (and internal newlines) are parsed into an instance of the appropriate type. The cluster
+/// does not include a trailing newline.
 ///
-/// As whitespace is potentially significant, it is not adjusted in any way before being handed to the parser.
+/// As whitespace is potentially significant, the input is normalized with the default
+/// [`Normalization`], which does not trim whitespace.
 ///
 /// If any record cannot be parsed, this prints the parse error on stderr and stops iteration.
 pub fn parse_two_phase_reader<'a, A, B, Reader, Filename>(
-    mut reader: Reader,
+    reader: Reader,
+    file_name: Filename,
+) -> Result<(A, impl 'a + Iterator<Item = B>), TwoPhaseError>
+where
+    A: 'a + FromStr,
+    <A as FromStr>::Err: Display,
+    B: 'a + FromStr,
+    <B as FromStr>::Err: Display,
+    Reader: 'a + BufRead,
+    Filename: 'a + Display,
+{
+    parse_two_phase_reader_with(reader, file_name, Normalization::default())
+}
+
+/// Parse the contents of the provided reader into a single instance of `A` and a stream of `B`,
+/// normalizing the input according to the provided policy.
+///
+/// Lines are batched into clusters separated by lines which are blank after normalization.
+/// Once a cluster has been collected, it (and internal newlines) are parsed into an instance
+/// of the appropriate type. The cluster does not include a trailing newline.
+///
+/// If any record cannot be parsed, this prints the parse error on stderr and stops iteration.
+pub fn parse_two_phase_reader_with<'a, A, B, Reader, Filename>(
+    reader: Reader,
     file_name: Filename,
+    normalization: Normalization,
 ) -> Result<(A, impl 'a + Iterator<Item = B>), TwoPhaseError>
 where
     A: 'a + FromStr,
@@ -233,25 +312,26 @@ where
     Reader: 'a + BufRead,
     Filename: 'a + Display,
 {
-    let mut buf = String::new();
-    let mut line: usize = 0;
+    let mut cluster = String::new();
+    let mut lines = NormalizedLines::new(reader, normalization);
 
-    let a = get_next_item(&mut buf, &mut line, &mut reader, &file_name)
-        .ok_or(TwoPhaseError::NoFirstLine)?;
+    let a =
+        get_next_item(&mut cluster, &mut lines, &file_name).ok_or(TwoPhaseError::NoFirstLine)?;
 
     Ok((
         a,
-        std::iter::from_fn(move || get_next_item(&mut buf, &mut line, &mut reader, &file_name))
-            .fuse(),
+        std::iter::from_fn(move || get_next_item(&mut cluster, &mut lines, &file_name)).fuse(),
     ))
 }
 
 /// Parse the file at the specified path into a single instance of `A` and a stream of `B`.
 ///
 /// Lines are batched into clusters separated by blank lines. Once a cluster has been collected,
-/// it (and internal newlines) are parsed into an instance of the appropriate type.
+/// it (and internal newlines) are parsed into an instance of the appropriate type. The cluster
+/// does not include a trailing newline.
 ///
-/// As whitespace is potentially significant, it is not adjusted in any way before being handed to the parser.
+/// As whitespace is potentially significant, the input is normalized with the default
+/// [`Normalization`], which does not trim whitespace.
 ///
 /// If any record cannot be parsed, this prints the parse error on stderr and stops iteration.
 pub fn parse_two_phase<'a, A, B>(
@@ -276,9 +356,11 @@ where
 /// Parse the provided data into a single instance of `A` and a stream of `B`.
 ///
 /// Lines are batched into clusters separated by blank lines. Once a cluster has been collected,
-/// it (and internal newlines) are parsed into an instance of the appropriate type.
+/// it (and internal newlines) are parsed into an instance of the appropriate type. The cluster
+/// does not include a trailing newline.
 ///
-/// As whitespace is potentially significant, it is not adjusted in any way before being handed to the parser.
+/// As whitespace is potentially significant, the input is normalized with the default
+/// [`Normalization`], which does not trim whitespace.
 ///
 /// If any record cannot be parsed, this prints the parse error on stderr and stops iteration.
 pub fn parse_two_phase_str<'a, A, B>(
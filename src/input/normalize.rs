@@ -0,0 +1,224 @@
+use std::io::BufRead;
+
+const BYTE_ORDER_MARK: char = '\u{feff}';
+
+/// Which whitespace is trimmed from each line of input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Trim {
+    /// Whitespace is preserved.
+    #[default]
+    None,
+    /// Trailing whitespace is removed; leading whitespace is preserved.
+    Trailing,
+    /// Leading and trailing whitespace are both removed.
+    Both,
+}
+
+/// A policy describing how raw input text is cleaned up before being handed to a parser.
+///
+/// Every parse function in this module applies a normalization policy, as do the `Map`
+/// parsers. Functions whose names end in `_with` accept an explicit policy; the others apply
+/// a sensible default, documented on each function.
+///
+/// Normalization is applied line by line:
+///
+/// - if `strip_bom` is set, a byte-order mark at the start of the input is removed
+/// - if `normalize_crlf` is set, a `\r` which ends a line is removed, so that `\r\n` line endings
+///   behave identically to `\n`
+/// - finally, whitespace is trimmed from the line according to `trim`
+///
+/// A line which is empty after normalization is considered blank, which is significant for
+/// functions which separate records by blank lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Normalization {
+    /// Remove a byte-order mark from the start of the input.
+    pub strip_bom: bool,
+    /// Treat `\r\n` line endings as `\n`.
+    pub normalize_crlf: bool,
+    /// Which whitespace to trim from each line.
+    pub trim: Trim,
+}
+
+impl Default for Normalization {
+    /// Strip the byte-order mark and normalize line endings, but preserve all other whitespace.
+    fn default() -> Self {
+        Normalization {
+            strip_bom: true,
+            normalize_crlf: true,
+            trim: Trim::None,
+        }
+    }
+}
+
+impl Normalization {
+    /// Leave the input exactly as it was, apart from splitting it into lines.
+    pub const fn raw() -> Self {
+        Normalization {
+            strip_bom: false,
+            normalize_crlf: false,
+            trim: Trim::None,
+        }
+    }
+
+    /// Like the default policy, but also trims leading and trailing whitespace from each line.
+    pub const fn trimmed() -> Self {
+        Normalization {
+            strip_bom: true,
+            normalize_crlf: true,
+            trim: Trim::Both,
+        }
+    }
+
+    /// Replace the trimming behavior of this policy.
+    pub const fn with_trim(mut self, trim: Trim) -> Self {
+        self.trim = trim;
+        self
+    }
+
+    /// Normalize a single line of input.
+    ///
+    /// `line` may or may not include its terminating `\n`; it is removed if present.
+    /// `is_first` must be `true` only for the first line of the input.
+    pub fn line<'a>(&self, line: &'a str, is_first: bool) -> &'a str {
+        let mut line = line.strip_suffix('\n').unwrap_or(line);
+        if is_first && self.strip_bom {
+            line = line.strip_prefix(BYTE_ORDER_MARK).unwrap_or(line);
+        }
+        if self.normalize_crlf {
+            line = line.strip_suffix('\r').unwrap_or(line);
+        }
+        match self.trim {
+            Trim::None => line,
+            Trim::Trailing => line.trim_end(),
+            Trim::Both => line.trim(),
+        }
+    }
+
+    /// Normalize an entire input.
+    ///
+    /// Lines are rejoined with `\n`. The output ends with `\n` exactly when the input did.
+    pub fn apply(&self, data: &str) -> String {
+        let mut out = String::with_capacity(data.len());
+        for (idx, line) in data.split_inclusive('\n').enumerate() {
+            out.push_str(self.line(line, idx == 0));
+            if line.ends_with('\n') {
+                out.push('\n');
+            }
+        }
+        out
+    }
+}
+
+/// Read normalized lines from a reader.
+pub(crate) struct NormalizedLines<Reader> {
+    reader: Reader,
+    buf: String,
+    line: usize,
+    normalization: Normalization,
+}
+
+impl<Reader: BufRead> NormalizedLines<Reader> {
+    pub(crate) fn new(reader: Reader, normalization: Normalization) -> Self {
+        NormalizedLines {
+            reader,
+            buf: String::new(),
+            line: 0,
+            normalization,
+        }
+    }
+
+    /// Read the next line, returning its 1-based line number and normalized content.
+    ///
+    /// Returns `Ok(None)` at the end of input.
+    pub(crate) fn try_next_line(&mut self) -> std::io::Result<Option<(usize, &str)>> {
+        self.buf.clear();
+        if self.reader.read_line(&mut self.buf)? == 0 {
+            return Ok(None);
+        }
+        self.line += 1;
+        Ok(Some((
+            self.line,
+            self.normalization.line(&self.buf, self.line == 1),
+        )))
+    }
+
+    /// Read the next line, returning its 1-based line number and normalized content.
+    ///
+    /// Returns `None` at the end of input or if the reader fails.
+    pub(crate) fn next_line(&mut self) -> Option<(usize, &str)> {
+        self.try_next_line().ok().flatten()
+    }
+
+    /// Read the next cluster of non-blank lines into `cluster`, returning the line number on which it began.
+    ///
+    /// Blank lines before the cluster are skipped. The lines of the cluster are joined with `\n`;
+    /// there is no trailing newline.
+    ///
+    /// Returns `None` if no non-blank lines remain.
+    pub(crate) fn next_cluster(&mut self, cluster: &mut String) -> Option<usize> {
+        cluster.clear();
+        let mut start = None;
+        while let Some((line_number, line)) = self.next_line() {
+            if line.is_empty() {
+                if start.is_some() {
+                    break;
+                }
+                continue;
+            }
+            if start.is_some() {
+                cluster.push('\n');
+            }
+            cluster.push_str(line);
+            start.get_or_insert(line_number);
+        }
+        start
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_crlf_and_bom() {
+        let normalization = Normalization::default();
+        assert_eq!(normalization.line("\u{feff}abc \r\n", true), "abc ");
+        assert_eq!(
+            normalization.line("\u{feff}abc \r\n", false),
+            "\u{feff}abc "
+        );
+        assert_eq!(Normalization::raw().line("abc\r\n", false), "abc\r");
+    }
+
+    #[test]
+    fn test_line_trim() {
+        let line = "  abc  \r\n";
+        assert_eq!(Normalization::default().line(line, false), "  abc  ");
+        assert_eq!(
+            Normalization::default()
+                .with_trim(Trim::Trailing)
+                .line(line, false),
+            "  abc"
+        );
+        assert_eq!(Normalization::trimmed().line(line, false), "abc");
+    }
+
+    #[test]
+    fn test_apply_preserves_final_newline() {
+        let normalization = Normalization::default();
+        assert_eq!(normalization.apply("\u{feff}a\r\nb\r\n"), "a\nb\n");
+        assert_eq!(normalization.apply("a\r\nb"), "a\nb");
+    }
+
+    #[test]
+    fn test_clusters() {
+        let data = "\r\na\r\nb\r\n\r\n\r\nc\r\n";
+        let mut lines = NormalizedLines::new(data.as_bytes(), Normalization::default());
+        let mut cluster = String::new();
+        assert_eq!(lines.next_cluster(&mut cluster), Some(2));
+        assert_eq!(cluster, "a\nb");
+        assert_eq!(lines.next_cluster(&mut cluster), Some(6));
+        assert_eq!(cluster, "c");
+        assert_eq!(lines.next_cluster(&mut cluster), None);
+    }
+}
@@ -0,0 +1,102 @@
+use super::{Normalization, NormalizedLines, TEST_DATA_FILENAME};
+use rayon::prelude::*;
+use std::{fmt::Display, fs::File, io::BufRead, io::BufReader, path::Path, str::FromStr};
+
+/// Parse the file at the specified path into a `Vec<T>`, parsing lines in parallel.
+///
+/// This behaves like [`parse`][super::parse], but the whole file is read before any line is
+/// parsed, and lines are then parsed on all available cores. It is worthwhile when parsing
+/// each line is expensive, or the input is very large.
+///
+/// If any record cannot be parsed, this prints the parse error on stderr, and the output contains
+/// only the records which precede it.
+///
+/// _Depends on the `rayon` feature._
+pub fn par_parse<T>(path: &Path) -> std::io::Result<Vec<T>>
+where
+    T: FromStr + Send,
+    <T as FromStr>::Err: Display + Send,
+{
+    let file = File::open(path)?;
+    par_parse_reader_with(
+        BufReader::new(file),
+        path.file_name()
+            .expect("File::open() didn't early return before now; qed")
+            .to_string_lossy(),
+        Normalization::trimmed(),
+    )
+}
+
+/// Parse the provided data into a `Vec<T>`, parsing lines in parallel.
+///
+/// See [`par_parse`].
+///
+/// _Depends on the `rayon` feature._
+pub fn par_parse_str<T>(data: &str) -> std::io::Result<Vec<T>>
+where
+    T: FromStr + Send,
+    <T as FromStr>::Err: Display + Send,
+{
+    par_parse_reader_with(
+        data.as_bytes(),
+        TEST_DATA_FILENAME,
+        Normalization::trimmed(),
+    )
+}
+
+/// Parse the contents of the provided reader into a `Vec<T>`, parsing lines in parallel.
+///
+/// Each line is normalized according to the provided policy. See [`par_parse`].
+///
+/// _Depends on the `rayon` feature._
+pub fn par_parse_reader_with<T, Reader, Filename>(
+    reader: Reader,
+    file_name: Filename,
+    normalization: Normalization,
+) -> std::io::Result<Vec<T>>
+where
+    T: FromStr + Send,
+    <T as FromStr>::Err: Display + Send,
+    Reader: BufRead,
+    Filename: Display,
+{
+    let mut lines = NormalizedLines::new(reader, normalization);
+    let mut records = Vec::new();
+    while let Some((_, record)) = lines.try_next_line()? {
+        records.push(record.to_owned());
+    }
+
+    let parsed: Vec<Result<T, <T as FromStr>::Err>> =
+        records.par_iter().map(|record| record.parse()).collect();
+
+    let mut out = Vec::with_capacity(parsed.len());
+    for (idx, result) in parsed.into_iter().enumerate() {
+        match result {
+            Ok(t) => out.push(t),
+            Err(e) => {
+                eprintln!("{}:{}: {} for {:?}", file_name, idx + 1, e, records[idx]);
+                break;
+            }
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_sequential() {
+        let data: String = (0..1000).map(|n| format!(" {}\r\n", n)).collect();
+        let parallel: Vec<u32> = par_parse_str(&data).unwrap();
+        let sequential: Vec<u32> = super::super::parse_str(&data).unwrap().collect();
+        assert_eq!(parallel, sequential);
+    }
+
+    #[test]
+    fn test_halts_on_error() {
+        let values: Vec<u32> = par_parse_str("1\n2\nx\n4").unwrap();
+        assert_eq!(values, vec![1, 2]);
+    }
+}
@@ -0,0 +1,199 @@
+use std::{fmt::Display, str::FromStr};
+
+/// A set of `key:value` fields, as found in passport-style puzzle inputs.
+///
+/// Fields are separated by any whitespace, including newlines. Within a field, the key
+/// and value are separated by the first `:`. Fields retain the order in which they
+/// appeared in the input, and each key may appear at most once.
+///
+/// This plugs into any of the parse functions, but is most useful with
+/// [`parse_newline_sep`][super::parse_newline_sep], where each record spans several lines.
+///
+/// ## Example
+///
+/// ```
+/// # use aoclib::input::{parse_newline_sep_str, Record};
+/// const EXAMPLE: &str = "
+/// ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
+/// byr:1937 iyr:2017 cid:147 hgt:183cm
+///
+/// hcl:#ae17e1 iyr:2013
+/// eyr:2024
+/// ";
+///
+/// let records: Vec<Record> = parse_newline_sep_str(EXAMPLE).unwrap().collect();
+/// assert_eq!(records.len(), 2);
+/// assert_eq!(records[0].get("ecl"), Some("gry"));
+/// assert_eq!(records[0].field::<u32>("byr").unwrap(), 1937);
+/// assert!(records[1].field::<u32>("byr").is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Record {
+    fields: Vec<(String, String)>,
+}
+
+impl Record {
+    /// Number of fields in this record.
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    /// `true` when this record has no fields.
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// `true` when this record contains a field with the given key.
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Get the raw value of a field.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Iterate over the keys and values of this record, in input order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.fields
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    /// Iterate over the keys of this record, in input order.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.fields.iter().map(|(key, _)| key.as_str())
+    }
+
+    /// Parse the value of a field into a `T`.
+    ///
+    /// It is an error if the field is missing or its value cannot be parsed.
+    pub fn field<T>(&self, key: &str) -> Result<T, FieldError>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: Display,
+    {
+        self.field_opt(key)?.ok_or_else(|| FieldError::Missing {
+            key: key.to_owned(),
+        })
+    }
+
+    /// Parse the value of a field into a `T`, if it is present.
+    ///
+    /// It is an error if the field is present but its value cannot be parsed.
+    pub fn field_opt<T>(&self, key: &str) -> Result<Option<T>, FieldError>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: Display,
+    {
+        self.get(key)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|err: <T as FromStr>::Err| FieldError::Parse {
+                        key: key.to_owned(),
+                        value: value.to_owned(),
+                        type_name: std::any::type_name::<T>(),
+                        message: err.to_string(),
+                    })
+            })
+            .transpose()
+    }
+}
+
+impl FromStr for Record {
+    type Err = RecordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fields: Vec<(String, String)> = Vec::new();
+        for token in s.split_whitespace() {
+            let (key, value) = token
+                .split_once(':')
+                .ok_or_else(|| RecordError::MissingSeparator(token.to_owned()))?;
+            if fields.iter().any(|(k, _)| k == key) {
+                return Err(RecordError::DuplicateKey(key.to_owned()));
+            }
+            fields.push((key.to_owned(), value.to_owned()));
+        }
+        Ok(Record { fields })
+    }
+}
+
+impl<'a> IntoIterator for &'a Record {
+    type Item = (&'a str, &'a str);
+    type IntoIter = Box<dyn 'a + Iterator<Item = Self::Item>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(self.iter())
+    }
+}
+
+/// A [`Record`] could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum RecordError {
+    #[error("field {0:?} has no `:` separating key and value")]
+    MissingSeparator(String),
+    #[error("key {0:?} appears more than once")]
+    DuplicateKey(String),
+}
+
+/// A field of a [`Record`] could not be extracted.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum FieldError {
+    #[error("field {key:?} is missing")]
+    Missing { key: String },
+    #[error("field {key:?}: parsing {value:?} as {type_name}: {message}")]
+    Parse {
+        key: String,
+        value: String,
+        type_name: &'static str,
+        message: String,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_order_preserved() {
+        let record: Record = "b:2\na:1 c:3".parse().unwrap();
+        assert_eq!(record.keys().collect::<Vec<_>>(), vec!["b", "a", "c"]);
+    }
+
+    #[test]
+    fn test_value_may_contain_separator() {
+        let record: Record = "time:12:30".parse().unwrap();
+        assert_eq!(record.get("time"), Some("12:30"));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            "a:1 b".parse::<Record>(),
+            Err(RecordError::MissingSeparator("b".into()))
+        );
+        assert_eq!(
+            "a:1 a:2".parse::<Record>(),
+            Err(RecordError::DuplicateKey("a".into()))
+        );
+    }
+
+    #[test]
+    fn test_field_errors() {
+        let record: Record = "hgt:183cm".parse().unwrap();
+        assert_eq!(
+            record.field::<u32>("byr"),
+            Err(FieldError::Missing { key: "byr".into() })
+        );
+        assert_eq!(record.field_opt::<u32>("byr"), Ok(None));
+        let err = record.field::<u32>("hgt").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            r#"field "hgt": parsing "183cm" as u32: invalid digit found in string"#
+        );
+    }
+}
@@ -0,0 +1,293 @@
+use super::tuple::{ElementError, ParseTuple};
+
+/// A compiled template for the [`scan!`][crate::scan] macro.
+///
+/// A template is literal text interspersed with `{}` placeholders. Literal braces can be
+/// written as `{{` and `}}`. Each placeholder captures the shortest text up to the next
+/// occurrence of the literal which follows it; the final placeholder captures everything up
+/// to the final literal, which must end the input.
+///
+/// Two placeholders may not be adjacent, as there would be no way to decide where one ends
+/// and the next begins.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanPattern {
+    /// Literal text surrounding the placeholders.
+    ///
+    /// There is always exactly one more literal than there are placeholders.
+    literals: Vec<String>,
+}
+
+impl ScanPattern {
+    /// Compile a template.
+    pub fn new(template: &str) -> Result<ScanPattern, TemplateError> {
+        let mut literals = Vec::new();
+        let mut current = String::new();
+        let mut chars = template.char_indices().peekable();
+
+        while let Some((idx, ch)) = chars.next() {
+            match (ch, chars.peek().map(|&(_, next)| next)) {
+                ('{', Some('{')) | ('}', Some('}')) => {
+                    chars.next();
+                    current.push(ch);
+                }
+                ('{', Some('}')) => {
+                    chars.next();
+                    if current.is_empty() && !literals.is_empty() {
+                        return Err(TemplateError::AdjacentPlaceholders(literals.len()));
+                    }
+                    literals.push(std::mem::take(&mut current));
+                }
+                ('{', _) | ('}', _) => return Err(TemplateError::UnmatchedBrace(idx)),
+                _ => current.push(ch),
+            }
+        }
+        literals.push(current);
+
+        Ok(ScanPattern { literals })
+    }
+
+    /// Number of placeholders in this template.
+    pub fn placeholders(&self) -> usize {
+        self.literals.len() - 1
+    }
+
+    /// Split `input` according to this template, returning the text captured by each placeholder.
+    pub fn captures<'a>(&self, input: &'a str) -> Result<Vec<Capture<'a>>, ScanError> {
+        let mut captures = Vec::with_capacity(self.placeholders());
+        let mut position = self.expect_literal(input, 0, &self.literals[0])?;
+
+        for (index, literal) in self.literals.iter().enumerate().skip(1) {
+            let rest = &input[position..];
+            let is_last = index == self.literals.len() - 1;
+            let len = if is_last {
+                if !rest.ends_with(literal.as_str()) {
+                    return Err(ScanError::MissingLiteral {
+                        literal: literal.clone(),
+                        column: column(input, input.len()),
+                    });
+                }
+                rest.len() - literal.len()
+            } else {
+                rest.find(literal.as_str())
+                    .ok_or_else(|| ScanError::MissingLiteral {
+                        literal: literal.clone(),
+                        column: column(input, position),
+                    })?
+            };
+
+            captures.push(Capture {
+                text: &rest[..len],
+                column: column(input, position),
+            });
+            position += len + literal.len();
+        }
+
+        if position != input.len() {
+            return Err(ScanError::TrailingInput {
+                column: column(input, position),
+            });
+        }
+
+        Ok(captures)
+    }
+
+    /// Parse `input` according to this template into a tuple of values.
+    ///
+    /// Each placeholder is parsed with the `FromStr` implementation of the corresponding tuple
+    /// element.
+    pub fn scan<T: ParseTuple>(&self, input: &str) -> Result<T, ScanError> {
+        if T::ARITY != self.placeholders() {
+            return Err(ScanError::Arity {
+                placeholders: self.placeholders(),
+                requested: T::ARITY,
+            });
+        }
+
+        let captures = self.captures(input)?;
+        let texts: Vec<_> = captures.iter().map(|capture| capture.text).collect();
+        T::parse_tuple(&texts).map_err(|source| ScanError::Placeholder {
+            column: captures[source.index].column,
+            source,
+        })
+    }
+
+    /// Ensure that `literal` appears in `input` at `position`, returning the position following it.
+    fn expect_literal(
+        &self,
+        input: &str,
+        position: usize,
+        literal: &str,
+    ) -> Result<usize, ScanError> {
+        if input[position..].starts_with(literal) {
+            Ok(position + literal.len())
+        } else {
+            Err(ScanError::MissingLiteral {
+                literal: literal.to_owned(),
+                column: column(input, position),
+            })
+        }
+    }
+}
+
+/// Text captured by a single placeholder of a [`ScanPattern`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capture<'a> {
+    /// The captured text.
+    pub text: &'a str,
+    /// 1-based column in the input at which the captured text begins.
+    pub column: usize,
+}
+
+/// Convert a byte offset into a 1-based column number, counted in characters.
+fn column(input: &str, byte_offset: usize) -> usize {
+    input[..byte_offset].chars().count() + 1
+}
+
+/// The template supplied to a [`ScanPattern`] was malformed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum TemplateError {
+    #[error("placeholder {0} immediately follows the previous placeholder")]
+    AdjacentPlaceholders(usize),
+    #[error("unmatched brace at byte {0}; use `{{{{` or `}}}}` for literal braces")]
+    UnmatchedBrace(usize),
+}
+
+/// An input did not match a [`ScanPattern`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ScanError {
+    #[error("expected {literal:?} at column {column}")]
+    MissingLiteral { literal: String, column: usize },
+    #[error("unexpected trailing input at column {column}")]
+    TrailingInput { column: usize },
+    #[error("placeholder at column {column}: {source}")]
+    Placeholder {
+        column: usize,
+        #[source]
+        source: ElementError,
+    },
+    #[error("template has {placeholders} placeholders but {requested} values were requested")]
+    Arity {
+        placeholders: usize,
+        requested: usize,
+    },
+}
+
+/// Parse a string according to a simple template.
+///
+/// The template is a string literal in which each `{}` is a placeholder. Each placeholder is
+/// parsed with the `FromStr` implementation of the corresponding type. The template is compiled
+/// only once, no matter how many times the macro's expansion is evaluated.
+///
+/// This evaluates to `Result<(T0, T1, ...), ScanError>`. When a single type is given without
+/// parentheses, it evaluates to `Result<T, ScanError>`.
+///
+/// See [`ScanPattern`] for details about how placeholders are matched.
+///
+/// ## Panics
+///
+/// If the template is malformed.
+///
+/// ## Example
+///
+/// ```
+/// # use aoclib::scan;
+/// let (count, from, to) = scan!("move 3 from 1 to 2", "move {} from {} to {}" => (u32, usize, usize)).unwrap();
+/// assert_eq!((count, from, to), (3, 1, 2));
+///
+/// let err = scan!("move many from 1 to 2", "move {} from {} to {}" => (u32, usize, usize)).unwrap_err();
+/// assert_eq!(
+///     err.to_string(),
+///     r#"placeholder at column 6: element 0 (u32) from "many": invalid digit found in string"#,
+/// );
+///
+/// let id: u32 = scan!("Game 12", "Game {}" => u32).unwrap();
+/// assert_eq!(id, 12);
+/// ```
+#[macro_export]
+macro_rules! scan {
+    (@pattern $template:literal) => {{
+        static PATTERN: ::std::sync::OnceLock<$crate::input::ScanPattern> =
+            ::std::sync::OnceLock::new();
+        PATTERN.get_or_init(|| {
+            $crate::input::ScanPattern::new($template).expect("scan! template must be well-formed")
+        })
+    }};
+    ($input:expr, $template:literal => ($($t:ty),+ $(,)?)) => {
+        $crate::scan!(@pattern $template).scan::<($($t,)+)>($input)
+    };
+    ($input:expr, $template:literal => $t:ty) => {
+        $crate::scan!(@pattern $template)
+            .scan::<($t,)>($input)
+            .map(|(value,)| value)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escaped_braces() {
+        let pattern = ScanPattern::new("{{{}}}").unwrap();
+        assert_eq!(pattern.scan::<(i32,)>("{-5}"), Ok((-5,)));
+    }
+
+    #[test]
+    fn test_adjacent_placeholders_rejected() {
+        assert_eq!(
+            ScanPattern::new("{}{}"),
+            Err(TemplateError::AdjacentPlaceholders(1))
+        );
+    }
+
+    #[test]
+    fn test_unmatched_brace_rejected() {
+        assert_eq!(
+            ScanPattern::new("a } b"),
+            Err(TemplateError::UnmatchedBrace(2))
+        );
+    }
+
+    #[test]
+    fn test_missing_literal() {
+        let result = crate::scan!("move 3 to 2", "move {} from {} to {}" => (u32, usize, usize));
+        assert_eq!(
+            result,
+            Err(ScanError::MissingLiteral {
+                literal: " from ".into(),
+                column: 6,
+            })
+        );
+    }
+
+    #[test]
+    fn test_trailing_input() {
+        let result = crate::scan!("x=1!", "x={}!" => i32);
+        assert_eq!(result, Ok(1));
+        let result = crate::scan!("x=1!?", "x={}!" => i32);
+        assert!(matches!(result, Err(ScanError::MissingLiteral { .. })));
+        let pattern = ScanPattern::new("x").unwrap();
+        assert_eq!(
+            pattern.captures("xy"),
+            Err(ScanError::TrailingInput { column: 2 })
+        );
+    }
+
+    #[test]
+    fn test_arity_mismatch() {
+        let pattern = ScanPattern::new("{} {}").unwrap();
+        assert_eq!(
+            pattern.scan::<(u8,)>("1 2"),
+            Err(ScanError::Arity {
+                placeholders: 2,
+                requested: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn test_last_placeholder_is_greedy() {
+        let result = crate::scan!("a: b: c", "{}: {}" => (String, String));
+        assert_eq!(result, Ok(("a".to_string(), "b: c".to_string())));
+    }
+}
@@ -0,0 +1,88 @@
+use std::{fmt::Display, str::FromStr};
+
+/// A single element of a tuple failed to parse.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("element {index} ({type_name}) from {text:?}: {message}")]
+pub struct ElementError {
+    /// Position of the element within the tuple.
+    pub index: usize,
+    /// Name of the type into which the element was being parsed.
+    pub type_name: &'static str,
+    /// Text which failed to parse.
+    pub text: String,
+    /// Message produced by the element type's `FromStr` implementation.
+    pub message: String,
+}
+
+impl ElementError {
+    pub(crate) fn new<T>(index: usize, text: &str, err: impl Display) -> ElementError {
+        ElementError {
+            index,
+            type_name: std::any::type_name::<T>(),
+            text: text.to_owned(),
+            message: err.to_string(),
+        }
+    }
+}
+
+/// Parse a single element of a tuple or list, producing a helpful error on failure.
+pub(crate) fn parse_element<T>(index: usize, text: &str) -> Result<T, ElementError>
+where
+    T: FromStr,
+    <T as FromStr>::Err: Display,
+{
+    text.parse()
+        .map_err(|err| ElementError::new::<T>(index, text, err))
+}
+
+/// A tuple each of whose elements can be parsed from a string slice.
+///
+/// This is implemented for tuples of up to 12 elements, where every element type implements
+/// `FromStr`. Element types may differ from each other.
+pub trait ParseTuple: Sized {
+    /// Number of elements in this tuple.
+    const ARITY: usize;
+
+    /// Parse each element of this tuple from the corresponding item.
+    ///
+    /// ## Panics
+    ///
+    /// If `items.len() != Self::ARITY`.
+    fn parse_tuple(items: &[&str]) -> Result<Self, ElementError>;
+}
+
+macro_rules! impl_parse_tuple {
+    ($arity:literal; $($idx:tt => $t:ident),+) => {
+        impl<$($t),+> ParseTuple for ($($t,)+)
+        where
+            $(
+                $t: FromStr,
+                <$t as FromStr>::Err: Display,
+            )+
+        {
+            const ARITY: usize = $arity;
+
+            fn parse_tuple(items: &[&str]) -> Result<Self, ElementError> {
+                assert_eq!(
+                    items.len(),
+                    Self::ARITY,
+                    "item count must match tuple arity"
+                );
+                Ok(($(parse_element::<$t>($idx, items[$idx])?,)+))
+            }
+        }
+    };
+}
+
+impl_parse_tuple!(1; 0 => A);
+impl_parse_tuple!(2; 0 => A, 1 => B);
+impl_parse_tuple!(3; 0 => A, 1 => B, 2 => C);
+impl_parse_tuple!(4; 0 => A, 1 => B, 2 => C, 3 => D);
+impl_parse_tuple!(5; 0 => A, 1 => B, 2 => C, 3 => D, 4 => E);
+impl_parse_tuple!(6; 0 => A, 1 => B, 2 => C, 3 => D, 4 => E, 5 => F);
+impl_parse_tuple!(7; 0 => A, 1 => B, 2 => C, 3 => D, 4 => E, 5 => F, 6 => G);
+impl_parse_tuple!(8; 0 => A, 1 => B, 2 => C, 3 => D, 4 => E, 5 => F, 6 => G, 7 => H);
+impl_parse_tuple!(9; 0 => A, 1 => B, 2 => C, 3 => D, 4 => E, 5 => F, 6 => G, 7 => H, 8 => I);
+impl_parse_tuple!(10; 0 => A, 1 => B, 2 => C, 3 => D, 4 => E, 5 => F, 6 => G, 7 => H, 8 => I, 9 => J);
+impl_parse_tuple!(11; 0 => A, 1 => B, 2 => C, 3 => D, 4 => E, 5 => F, 6 => G, 7 => H, 8 => I, 9 => J, 10 => K);
+impl_parse_tuple!(12; 0 => A, 1 => B, 2 => C, 3 => D, 4 => E, 5 => F, 6 => G, 7 => H, 8 => I, 9 => J, 10 => K, 11 => L);
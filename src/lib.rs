@@ -1,8 +1,14 @@
 pub mod config;
+pub mod cycle;
 pub mod data_structures;
+pub mod expression;
 pub mod geometry;
 pub mod input;
 pub mod numbers;
+pub mod search;
 pub mod website;
 
 pub use input::{parse, CommaSep};
+
+#[cfg(feature = "derive")]
+pub use input::AocParse;
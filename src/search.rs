@@ -0,0 +1,407 @@
+//! Searches over arbitrary state spaces.
+//!
+//! Each search starts from a single state, and explores by way of a successor function until it
+//! finds a state which satisfies the goal predicate. States can be anything which is `Clone + Eq +
+//! Hash`: a position, a set of collected keys, the floors of every item in a building.
+//!
+//! All searches return a [`SearchResult`], which records the cost and path to the goal, and the
+//! number of states the search expanded along the way.
+//!
+//! For navigation on a [`Map`][crate::geometry::Map], prefer
+//! [`Map::navigate`][crate::geometry::Map::navigate]: it is specialized for grids, and is much
+//! faster than the equivalent general search.
+
+use num::Zero;
+use std::{
+    cmp::Ordering,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, HashSet, VecDeque},
+    hash::Hash,
+};
+
+/// The outcome of a successful search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult<State, Cost> {
+    /// Total cost of the path.
+    pub cost: Cost,
+    /// Every state along the path, from the start to the goal inclusive.
+    pub path: Vec<State>,
+    /// The number of states which the search expanded.
+    pub visited: usize,
+}
+
+impl<State, Cost> SearchResult<State, Cost> {
+    /// The goal state at the end of the path.
+    pub fn goal(&self) -> &State {
+        self.path
+            .last()
+            .expect("a path always contains at least the start state")
+    }
+}
+
+/// Every state seen by a search, and the state from which it was first (or most cheaply) reached.
+struct Arena<State> {
+    states: Vec<State>,
+    parents: Vec<usize>,
+    indices: HashMap<State, usize>,
+}
+
+impl<State: Clone + Eq + Hash> Arena<State> {
+    fn new(start: State) -> Self {
+        Arena {
+            states: vec![start.clone()],
+            parents: vec![0],
+            indices: std::iter::once((start, 0)).collect(),
+        }
+    }
+
+    /// Get the index of a state, and whether it is new to the arena.
+    ///
+    /// New states are inserted with the given parent.
+    fn insert(&mut self, state: State, parent: usize) -> (usize, bool) {
+        match self.indices.entry(state) {
+            Entry::Occupied(entry) => (*entry.get(), false),
+            Entry::Vacant(entry) => {
+                let index = self.states.len();
+                self.states.push(entry.key().clone());
+                self.parents.push(parent);
+                entry.insert(index);
+                (index, true)
+            }
+        }
+    }
+
+    /// The path from the start to the state at `index`.
+    fn path_to(&self, mut index: usize) -> Vec<State> {
+        let mut path = vec![self.states[index].clone()];
+        while index != 0 {
+            index = self.parents[index];
+            path.push(self.states[index].clone());
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// Breadth-first search, where every step has a cost of 1.
+///
+/// Finds a path to the goal with the fewest steps.
+pub fn bfs<State, Successors>(
+    start: State,
+    mut successors: impl FnMut(&State) -> Successors,
+    mut is_goal: impl FnMut(&State) -> bool,
+) -> Option<SearchResult<State, usize>>
+where
+    State: Clone + Eq + Hash,
+    Successors: IntoIterator<Item = State>,
+{
+    let mut arena = Arena::new(start);
+    let mut depths = vec![0];
+    let mut queue = VecDeque::new();
+    queue.push_back(0);
+    let mut visited = 0;
+
+    while let Some(index) = queue.pop_front() {
+        visited += 1;
+        if is_goal(&arena.states[index]) {
+            return Some(SearchResult {
+                cost: depths[index],
+                path: arena.path_to(index),
+                visited,
+            });
+        }
+
+        for successor in successors(&arena.states[index]) {
+            let (successor, is_new) = arena.insert(successor, index);
+            if is_new {
+                depths.push(depths[index] + 1);
+                queue.push_back(successor);
+            }
+        }
+    }
+
+    None
+}
+
+/// Dijkstra's algorithm.
+///
+/// Each successor is paired with the cost of the step which reaches it. Finds a path to the goal
+/// with the least total cost.
+pub fn dijkstra<State, Cost, Successors>(
+    start: State,
+    successors: impl FnMut(&State) -> Successors,
+    is_goal: impl FnMut(&State) -> bool,
+) -> Option<SearchResult<State, Cost>>
+where
+    State: Clone + Eq + Hash,
+    Cost: Copy + Ord + Zero,
+    Successors: IntoIterator<Item = (State, Cost)>,
+{
+    a_star(start, successors, is_goal, |_| Cost::zero())
+}
+
+/// Open set entry for [`a_star`].
+struct Node<Cost> {
+    estimate: Cost,
+    cost: Cost,
+    index: usize,
+}
+
+impl<Cost: Ord> PartialEq for Node<Cost> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<Cost: Ord> Eq for Node<Cost> {}
+
+// `BinaryHeap` is a max-heap; reverse the estimates so it pops the best guess first.
+// Among equal estimates, prefer the node furthest along its path.
+impl<Cost: Ord> Ord for Node<Cost> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .estimate
+            .cmp(&self.estimate)
+            .then_with(|| self.cost.cmp(&other.cost))
+            .then_with(|| self.index.cmp(&other.index))
+    }
+}
+
+impl<Cost: Ord> PartialOrd for Node<Cost> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A* search.
+///
+/// Each successor is paired with the cost of the step which reaches it. The heuristic estimates
+/// the remaining cost from a state to the goal; the result is only guaranteed to be the cheapest
+/// path if the heuristic never overestimates.
+// https://en.wikipedia.org/wiki/A*_search_algorithm#Pseudocode
+pub fn a_star<State, Cost, Successors>(
+    start: State,
+    mut successors: impl FnMut(&State) -> Successors,
+    mut is_goal: impl FnMut(&State) -> bool,
+    mut heuristic: impl FnMut(&State) -> Cost,
+) -> Option<SearchResult<State, Cost>>
+where
+    State: Clone + Eq + Hash,
+    Cost: Copy + Ord + Zero,
+    Successors: IntoIterator<Item = (State, Cost)>,
+{
+    let mut open_set = BinaryHeap::new();
+    open_set.push(Node {
+        estimate: heuristic(&start),
+        cost: Cost::zero(),
+        index: 0,
+    });
+    let mut arena = Arena::new(start);
+    // indexed like the arena: cost of cheapest known path from start to state
+    let mut cheapest_path_cost = vec![Cost::zero()];
+    let mut visited = 0;
+
+    while let Some(Node { cost, index, .. }) = open_set.pop() {
+        // stale entry: a cheaper path to this state was found after this one was queued
+        if cost > cheapest_path_cost[index] {
+            continue;
+        }
+
+        visited += 1;
+        if is_goal(&arena.states[index]) {
+            return Some(SearchResult {
+                cost,
+                path: arena.path_to(index),
+                visited,
+            });
+        }
+
+        for (successor, step_cost) in successors(&arena.states[index]) {
+            let tentative_cost = cost + step_cost;
+            let (successor, is_new) = arena.insert(successor, index);
+            if is_new {
+                cheapest_path_cost.push(tentative_cost);
+            } else if tentative_cost < cheapest_path_cost[successor] {
+                cheapest_path_cost[successor] = tentative_cost;
+                arena.parents[successor] = index;
+            } else {
+                continue;
+            }
+            open_set.push(Node {
+                estimate: tentative_cost + heuristic(&arena.states[successor]),
+                cost: tentative_cost,
+                index: successor,
+            });
+        }
+    }
+
+    None
+}
+
+/// Iterative-deepening depth-first search, where every step has a cost of 1.
+///
+/// Performs successive depth-limited searches with increasing limits, up to and including
+/// `max_depth`. Like [`bfs`], it finds a path to the goal with the fewest steps, but it only keeps
+/// the current path in memory, at the expense of revisiting states.
+///
+/// States are never repeated within a single path, but may be visited many times via different
+/// paths. `visited` counts every expansion across all iterations.
+pub fn iddfs<State, Successors>(
+    start: State,
+    mut successors: impl FnMut(&State) -> Successors,
+    mut is_goal: impl FnMut(&State) -> bool,
+    max_depth: usize,
+) -> Option<SearchResult<State, usize>>
+where
+    State: Clone + Eq + Hash,
+    Successors: IntoIterator<Item = State>,
+{
+    let mut visited = 0;
+    for depth_limit in 0..=max_depth {
+        let mut path = vec![start.clone()];
+        let mut on_path: HashSet<State> = std::iter::once(start.clone()).collect();
+        // one iterator of untried successors per state on the path, except the last
+        let mut frontier: Vec<<Successors as IntoIterator>::IntoIter> = Vec::new();
+        let mut truncated = false;
+
+        loop {
+            let current = path.last().expect("path is never empty within the loop");
+            if frontier.len() == path.len() - 1 {
+                // newly reached state: expand it
+                visited += 1;
+                if is_goal(current) {
+                    return Some(SearchResult {
+                        cost: path.len() - 1,
+                        path,
+                        visited,
+                    });
+                }
+                if path.len() - 1 == depth_limit {
+                    truncated = true;
+                } else {
+                    frontier.push(successors(current).into_iter());
+                    continue;
+                }
+            } else {
+                // returned to this state: try its next successor
+                let next = frontier
+                    .last_mut()
+                    .expect("frontier matches path")
+                    .find(|state| !on_path.contains(state));
+                if let Some(next) = next {
+                    on_path.insert(next.clone());
+                    path.push(next);
+                    continue;
+                }
+                frontier.pop();
+            }
+
+            // backtrack
+            let finished = path.pop().expect("path is never empty within the loop");
+            on_path.remove(&finished);
+            if path.is_empty() {
+                break;
+            }
+        }
+
+        if !truncated {
+            // the whole reachable space fit within the depth limit; deeper searches can't help
+            return None;
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::{map::Traversable, Direction, Map, Point};
+
+    /// The classic water jugs puzzle: measure 4 liters with a 3 liter and a 5 liter jug.
+    fn jugs(&(small, large): &(u8, u8)) -> Vec<(u8, u8)> {
+        let pour_into_large = small.min(5 - large);
+        let pour_into_small = large.min(3 - small);
+        vec![
+            (3, large),
+            (small, 5),
+            (0, large),
+            (small, 0),
+            (small - pour_into_large, large + pour_into_large),
+            (small + pour_into_small, large - pour_into_small),
+        ]
+    }
+
+    #[test]
+    fn test_bfs_and_iddfs_agree() {
+        let is_goal = |&(_, large): &(u8, u8)| large == 4;
+        let breadth_first = bfs((0, 0), jugs, is_goal).unwrap();
+        let deepening = iddfs((0, 0), jugs, is_goal, 10).unwrap();
+        assert_eq!(breadth_first.cost, 6);
+        assert_eq!(breadth_first.path.len(), 7);
+        assert_eq!(deepening.cost, breadth_first.cost);
+        assert_eq!(deepening.goal().1, 4);
+        assert!(deepening.visited > breadth_first.visited);
+
+        assert!(iddfs((0, 0), jugs, is_goal, 5).is_none());
+        assert!(bfs((0, 0), jugs, |&(_, large)| large == 6).is_none());
+        assert!(iddfs((0, 0), jugs, |&(_, large)| large == 6, 100).is_none());
+    }
+
+    #[test]
+    fn test_dijkstra_prefers_cheap_detour() {
+        // 0 -> 3 directly costs 10; via 1 and 2 costs 3
+        let edges = |&node: &u8| -> Vec<(u8, u32)> {
+            match node {
+                0 => vec![(3, 10), (1, 1)],
+                1 => vec![(2, 1)],
+                2 => vec![(3, 1)],
+                _ => vec![],
+            }
+        };
+        let result = dijkstra(0, edges, |&node| node == 3).unwrap();
+        assert_eq!(result.cost, 3);
+        assert_eq!(result.path, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_a_star_matches_map_navigate() {
+        let map = Map::procedural(30, 20, |point| {
+            if point.y % 4 == 2 && point.x != (point.y * 7) % 30 {
+                Traversable::Obstructed
+            } else {
+                Traversable::Free
+            }
+        });
+        let (from, to) = (map.bottom_left(), map.top_right());
+
+        let result = a_star(
+            from,
+            |&position| {
+                map.orthogonal_adjacencies(position)
+                    .filter(|&neighbor| map[neighbor] == Traversable::Free)
+                    .map(|neighbor| (neighbor, 1))
+                    .collect::<Vec<_>>()
+            },
+            |&position| position == to,
+            |&position: &Point| (to - position).manhattan() as u32,
+        )
+        .unwrap();
+        let navigated: Vec<Direction> = map.navigate(from, to).unwrap();
+
+        assert_eq!(result.cost as usize, navigated.len());
+        assert_eq!(*result.goal(), to);
+        let unguided = dijkstra(
+            from,
+            |&position| {
+                map.orthogonal_adjacencies(position)
+                    .filter(|&neighbor| map[neighbor] == Traversable::Free)
+                    .map(|neighbor| (neighbor, 1))
+                    .collect::<Vec<_>>()
+            },
+            |&position| position == to,
+        )
+        .unwrap();
+        assert_eq!(unguided.cost, result.cost);
+        assert!(unguided.visited >= result.visited);
+    }
+}
@@ -0,0 +1,113 @@
+//! Run with: `cargo test --test windows_line_endings`
+
+use aoclib::{
+    geometry::{tile::Bool, Map},
+    input::{
+        parse, parse_borrowed_str, parse_newline_sep, parse_newline_sep_borrowed_str,
+        parse_newline_sep_str, parse_reader_with, parse_str, parse_two_phase_str, Normalization,
+        Trim,
+    },
+};
+use std::{convert::TryFrom, io::Cursor, path::PathBuf};
+
+/// An input file as it might look after being saved by a Windows text editor.
+const WINDOWS_LINES: &str = "\u{feff}1\r\n2  \r\n3\r\n";
+
+const WINDOWS_CLUSTERS: &str = "\u{feff}a\r\nb\r\n\r\nc  \r\n\r\n";
+
+const WINDOWS_MAP: &str = "\u{feff}#.\r\n.#\r\n";
+
+/// Write `contents` to a temporary file unique to this test.
+fn temp_file(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "aoclib-windows-line-endings-{}-{}",
+        std::process::id(),
+        name
+    ));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn test_lines_from_str() {
+    let values: Vec<u32> = parse_str(WINDOWS_LINES).unwrap().collect();
+    assert_eq!(values, vec![1, 2, 3]);
+}
+
+#[test]
+fn test_lines_from_file() {
+    let path = temp_file("lines", WINDOWS_LINES);
+    let values: Vec<u32> = parse(&path).unwrap().collect();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(values, vec![1, 2, 3]);
+}
+
+#[test]
+fn test_borrowed_lines() {
+    let values: Vec<&str> = parse_borrowed_str(WINDOWS_LINES).collect();
+    assert_eq!(values, vec!["1", "2", "3"]);
+}
+
+#[test]
+fn test_clusters_from_str() {
+    let clusters: Vec<String> = parse_newline_sep_str(WINDOWS_CLUSTERS).unwrap().collect();
+    assert_eq!(clusters, vec!["a\nb", "c  "]);
+}
+
+#[test]
+fn test_clusters_from_file() {
+    let path = temp_file("clusters", WINDOWS_CLUSTERS);
+    let clusters: Vec<String> = parse_newline_sep(&path).unwrap().collect();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(clusters, vec!["a\nb", "c  "]);
+}
+
+#[test]
+fn test_borrowed_clusters() {
+    let mut buf = String::new();
+    let clusters: Vec<&str> = parse_newline_sep_borrowed_str(WINDOWS_CLUSTERS, &mut buf).collect();
+    assert_eq!(clusters, vec!["a\nb", "c  "]);
+}
+
+#[test]
+fn test_clusters_match_unix_input() {
+    let unix = WINDOWS_CLUSTERS
+        .trim_start_matches('\u{feff}')
+        .replace("\r\n", "\n");
+    let windows: Vec<String> = parse_newline_sep_str(WINDOWS_CLUSTERS).unwrap().collect();
+    let unix: Vec<String> = parse_newline_sep_str(&unix).unwrap().collect();
+    assert_eq!(windows, unix);
+}
+
+#[test]
+fn test_two_phase() {
+    let (first, rest) = parse_two_phase_str::<String, String>(WINDOWS_CLUSTERS).unwrap();
+    assert_eq!(first, "a\nb");
+    assert_eq!(rest.collect::<Vec<_>>(), vec!["c  "]);
+}
+
+#[test]
+fn test_configurable_trim() {
+    let values: Vec<String> = parse_reader_with(
+        Cursor::new(WINDOWS_LINES),
+        "test",
+        Normalization::default().with_trim(Trim::None),
+    )
+    .unwrap()
+    .collect();
+    assert_eq!(values, vec!["1", "2  ", "3"]);
+}
+
+#[test]
+fn test_map() {
+    let map = <Map<Bool> as TryFrom<&str>>::try_from(WINDOWS_MAP).unwrap();
+    assert_eq!(map.width(), 2);
+    assert_eq!(map.height(), 2);
+    assert_eq!(map.to_string(), "#.\n.#\n");
+}
+
+#[test]
+fn test_map_raw_normalization_rejects_carriage_return() {
+    let result = Map::<Bool>::try_from_with(WINDOWS_MAP.as_bytes(), Normalization::raw());
+    assert!(result.is_err());
+}